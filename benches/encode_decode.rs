@@ -1,6 +1,10 @@
 use std::{cmp::max, time::Duration};
 
-use cobs_simd::{cobs_encode_to, encoded_size_upper_bound, Method};
+use cobs_simd::{
+    cobs_decode_to, cobs_encode_to, cobs_zpe_encode_to, cobs_zpe_encoded_size_upper_bound,
+    decoded_size_upper_bound, encoded_size_upper_bound, try_cobs_decode_to, try_cobs_zpe_decode_to,
+    DecodeMethod, Method,
+};
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use rand::{RngCore, SeedableRng};
 use rand_pcg::Pcg64Mcg;
@@ -48,6 +52,102 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         }
     }
     group.finish();
+
+    let mut group = c.benchmark_group("decoding");
+
+    for size in [1000, 5000, 10000] {
+        group.throughput(criterion::Throughput::Bytes(size as u64));
+        group.warm_up_time(Duration::from_millis(500));
+        group.measurement_time(Duration::from_secs(1));
+
+        let mut data = vec![0_u8; size];
+        rng.fill_bytes(&mut data);
+
+        // corncobs output includes the trailing delimiter, which cobs_decode_to accepts as well
+        let mut encoded_data = vec![0; corncobs::max_encoded_len(size)];
+        let encoded_length = corncobs::encode_buf(&data, &mut encoded_data);
+        encoded_data.truncate(encoded_length);
+        let slice: &[u8] = &encoded_data;
+
+        let mut output_data = vec![0; decoded_size_upper_bound(encoded_length)];
+        let output_slice: &mut [u8] = &mut output_data;
+
+        group.bench_with_input(
+            BenchmarkId::new("corncobs", size),
+            slice,
+            |b, input_data| {
+                b.iter(|| corncobs::decode_buf(input_data, output_slice));
+            },
+        );
+
+        for method in DecodeMethod::iter() {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{method}"), size),
+                slice,
+                |b, input_data| {
+                    b.iter(|| cobs_decode_to(input_data, output_slice, method.clone()));
+                },
+            );
+        }
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("zero_heavy");
+
+    for size in [1000, 5000, 10000] {
+        group.throughput(criterion::Throughput::Bytes(size as u64));
+        group.warm_up_time(Duration::from_millis(500));
+        group.measurement_time(Duration::from_secs(1));
+
+        // Sensor readings: 16 bit values stored as 32 bit little endian, so every sample ends with a pair of zeros
+        let data: Vec<u8> = (0..size / 4)
+            .flat_map(|_| (rng.next_u32() & 0xFFFF).to_le_bytes())
+            .collect();
+        let slice: &[u8] = &data;
+
+        let mut cobs_encoded = vec![0; encoded_size_upper_bound(data.len())];
+        let cobs_length = cobs_encode_to(slice, &mut cobs_encoded, Method::Auto);
+        cobs_encoded.truncate(cobs_length);
+        let mut zpe_encoded = vec![0; cobs_zpe_encoded_size_upper_bound(data.len())];
+        let zpe_length = cobs_zpe_encode_to(slice, &mut zpe_encoded);
+        zpe_encoded.truncate(zpe_length);
+        println!(
+            "zero_heavy/{size}: COBS {cobs_length} bytes, COBS/ZPE {zpe_length} bytes encoded"
+        );
+
+        let mut output_data = vec![0; 2 * cobs_zpe_encoded_size_upper_bound(data.len())];
+        let output_slice: &mut [u8] = &mut output_data;
+
+        group.bench_with_input(
+            BenchmarkId::new("encode_cobs", size),
+            slice,
+            |b, input_data| {
+                b.iter(|| cobs_encode_to(input_data, output_slice, Method::Auto));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("encode_cobs_zpe", size),
+            slice,
+            |b, input_data| {
+                b.iter(|| cobs_zpe_encode_to(input_data, output_slice));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("decode_cobs", size),
+            &cobs_encoded[..],
+            |b, input_data| {
+                b.iter(|| try_cobs_decode_to(input_data, output_slice, DecodeMethod::Simd16));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("decode_cobs_zpe", size),
+            &zpe_encoded[..],
+            |b, input_data| {
+                b.iter(|| try_cobs_zpe_decode_to(input_data, output_slice));
+            },
+        );
+    }
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
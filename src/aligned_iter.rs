@@ -1,4 +1,4 @@
-use std::cmp::min;
+use core::cmp::min;
 
 pub struct AlignedIter<'a> {
     data: &'a [u8],
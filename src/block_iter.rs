@@ -1,8 +1,24 @@
-use std::{cmp::min, marker::PhantomData};
+use core::{cmp::min, marker::PhantomData};
 
 #[allow(unused)]
 use crate::aligned_iter::AlignedIter;
 
+/// Iterator over the blocks of data between zeros, as used for COBS groups
+///
+/// Blocks end at the next zero, which is not part of any block, or after `max_block_size` non-zero bytes.
+/// A zero directly after such a complete block yields an empty block.
+/// The data is treated as if a zero was appended, so the last block ends at the end of the data,
+/// and data ending with a zero yields a final empty block. After a complete block at the end of the data,
+/// no empty block follows.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{BlockIter, PortableBlocks};
+///
+/// let blocks: Vec<_> = BlockIter::<PortableBlocks>::new(&[1, 2, 0, 3, 4, 5, 0], 2).collect();
+/// assert_eq!(blocks, [&[1, 2][..], &[], &[3, 4], &[5], &[]]);
+/// ```
 pub struct BlockIter<'a, ZeroIndexMethod>
 where
     ZeroIndexMethod: NextZeroIndex,
@@ -13,30 +29,64 @@ where
     zero_index_method: PhantomData<ZeroIndexMethod>,
 }
 
-impl<'a, T: NextZeroIndex + Default> BlockIter<'a, T> {
-    pub fn new(input_data: &[u8], max_block_size: usize) -> BlockIter<T> {
+impl<'a, T: NextZeroIndex> BlockIter<'a, T> {
+    /// Splits `input_data` into blocks of at most `max_block_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_block_size` is 0.
+    pub fn new(input_data: &'a [u8], max_block_size: usize) -> BlockIter<'a, T> {
+        assert!(max_block_size > 0, "max_block_size must not be 0");
         BlockIter {
             input_data,
             processed: 0,
             max_block_size,
-            zero_index_method: Default::default(),
+            zero_index_method: PhantomData,
         }
     }
 }
 
-pub trait NextZeroIndex: Default {
-    fn next_zero_index(data: &[u8]) -> Option<usize>;
+/// Zero search, used to find the groups of a COBS frame
+///
+/// Implement this to plug a custom zero search (e.g. using a platform-specific accelerator) into [BlockIter]
+/// and [cobs_encode_to_with](crate::cobs_encode_to_with).
+/// Implementations are stateless, only the associated functions are used.
+///
+/// # Contract
+///
+/// [next_byte_index](NextZeroIndex::next_byte_index) must return the index of the first occurrence of `byte`
+/// in `data`, or `None` if `data` does not contain `byte`, for any length of `data` including 0.
+/// The result must be the same as for `data.iter().position(|x| *x == byte)`, see [IterPosition].
+/// Encoders and decoders rely on this for correct output, but not for memory safety.
+pub trait NextZeroIndex {
+    /// Returns the index of the first occurrence of `byte` in `data`, or `None`.
+    fn next_byte_index(data: &[u8], byte: u8) -> Option<usize>;
+
+    /// Returns the index of the first zero in `data`, or `None`.
+    #[inline]
+    fn next_zero_index(data: &[u8]) -> Option<usize> {
+        Self::next_byte_index(data, 0)
+    }
 }
 
+/// Byte-wise zero search, the reference for all other implementations
 #[derive(Default)]
-struct IterPosition {}
+pub struct IterPosition {}
 
 impl NextZeroIndex for IterPosition {
-    fn next_zero_index(data: &[u8]) -> Option<usize> {
-        data.iter().position(|x| *x == 0)
+    fn next_byte_index(data: &[u8], byte: u8) -> Option<usize> {
+        data.iter().position(|x| *x == byte)
     }
 }
 
+/// Zero search without target-specific intrinsics, used as fallback by the intrinsic backends
+///
+/// [SimdBlocksGeneric](crate::SimdBlocksGeneric)<16> with the `nightly` feature, [SimdBlocksSwar](crate::SimdBlocksSwar) otherwise.
+#[cfg(feature = "nightly")]
+pub type PortableBlocks = crate::next_zero_std_simd::SimdBlocksGeneric<16>;
+#[cfg(not(feature = "nightly"))]
+pub type PortableBlocks = crate::next_zero_swar::SimdBlocksSwar;
+
 impl<'a, T: NextZeroIndex> Iterator for BlockIter<'a, T> {
     type Item = &'a [u8];
 
@@ -82,10 +132,184 @@ impl<'a, T: NextZeroIndex> Iterator for BlockIter<'a, T> {
     }
 }
 
+/// Next piece of data found by [GroupSplitter]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Split {
+    /// `len` bytes followed by a zero, which ends the open group of `group_len` bytes
+    Zero { len: usize, group_len: usize },
+    /// `len` bytes completing a group of 254 non-zero bytes
+    Full { len: usize },
+    /// All `len` bytes of the data continue the open group
+    Open { len: usize },
+}
+
+/// Splits data into COBS groups like [BlockIter] with blocks of 254 bytes, for data that arrives piecewise
+///
+/// The length of the open group is kept between calls, so a group may span several pieces of data.
+pub(crate) struct GroupSplitter<Z: NextZeroIndex> {
+    run: usize,       // Number of data bytes in the open group
+    group_open: bool, // False after a complete group of 254 bytes, the next group is only required if more data follows
+    zero_index_method: PhantomData<Z>,
+}
+
+impl<Z: NextZeroIndex> GroupSplitter<Z> {
+    pub(crate) fn new() -> GroupSplitter<Z> {
+        GroupSplitter {
+            run: 0,
+            group_open: true,
+            zero_index_method: PhantomData,
+        }
+    }
+
+    /// Finds the next piece at the start of `data`, or `None` if `data` is empty.
+    ///
+    /// The caller continues with the data after the piece, including the zero for [Split::Zero].
+    pub(crate) fn split(&mut self, data: &[u8]) -> Option<Split> {
+        if data.is_empty() {
+            return None;
+        }
+
+        let upper_bound = min(254 - self.run, data.len());
+        match Z::next_zero_index(&data[..upper_bound]) {
+            Some(i) => {
+                let group_len = self.run + i;
+                self.run = 0;
+                self.group_open = true;
+                Some(Split::Zero { len: i, group_len })
+            }
+            None if self.run + upper_bound == 254 => {
+                self.run = 0;
+                self.group_open = false;
+                Some(Split::Full { len: upper_bound })
+            }
+            None => {
+                // Only reached at the end of data, otherwise the group would be complete
+                self.run += upper_bound;
+                self.group_open = true;
+                Some(Split::Open { len: upper_bound })
+            }
+        }
+    }
+
+    /// Number of data bytes in the open group
+    pub(crate) fn run(&self) -> usize {
+        self.run
+    }
+
+    /// Whether the frame needs a final group if it ends here
+    pub(crate) fn group_open(&self) -> bool {
+        self.group_open
+    }
+
+    /// Starts the next frame
+    pub(crate) fn reset(&mut self) {
+        *self = GroupSplitter::new();
+    }
+}
+
+impl<Z: NextZeroIndex> Default for GroupSplitter<Z> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    use crate::SimdBlocksAvx2;
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    use crate::SimdBlocksAvx512;
+    #[cfg(feature = "nightly")]
+    use crate::SimdBlocksGeneric;
+    #[cfg(target_arch = "aarch64")]
+    use crate::SimdBlocksNeon;
+    use crate::{
+        block_iter::{IterPosition, NextZeroIndex},
+        cobs_encode_to_vec, cobs_encode_to_with, encoded_size_upper_bound, SimdBlocksSwar,
+    };
+    use concat_idents::concat_idents;
+
+    /// Checks the contract of NextZeroIndex against the reference implementation
+    macro_rules! next_zero_index_tests {
+        ($name:ident, $type:ty) => {
+            concat_idents!(test_name = $name, _, matches_reference {
+                #[quickcheck]
+                fn test_name(input_data: Vec<u8>, byte: u8) -> bool {
+                    <$type>::next_byte_index(&input_data, byte)
+                        == IterPosition::next_byte_index(&input_data, byte)
+                        && <$type>::next_zero_index(&input_data)
+                            == IterPosition::next_zero_index(&input_data)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, finds_first_byte {
+                #[quickcheck]
+                fn test_name(input_data: Vec<u8>, position: usize, byte: u8) -> bool {
+                    let mut input_data = input_data;
+                    if !input_data.is_empty() {
+                        let len = input_data.len();
+                        input_data[position % len] = byte;
+                    }
+                    <$type>::next_byte_index(&input_data, byte)
+                        == input_data.iter().position(|x| *x == byte)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, all_lengths_and_positions {
+                #[test]
+                fn test_name() {
+                    for len in 0..300 {
+                        let data = vec![0x80; len];
+                        assert_eq!(<$type>::next_zero_index(&data), None);
+                        for position in 0..len {
+                            let mut data = data.clone();
+                            data[position] = 0;
+                            assert_eq!(<$type>::next_zero_index(&data), Some(position));
+                        }
+                    }
+                }
+            });
+
+            concat_idents!(test_name = $name, _, same_blocks {
+                #[quickcheck]
+                fn test_name(input_data: Vec<u8>, max_block_size: u8) -> bool {
+                    let max_block_size = max_block_size as usize + 1;
+                    BlockIter::<$type>::new(&input_data, max_block_size)
+                        .eq(BlockIter::<IterPosition>::new(&input_data, max_block_size))
+                }
+            });
+
+            concat_idents!(test_name = $name, _, encoding {
+                #[quickcheck]
+                fn test_name(input_data: Vec<u8>) -> bool {
+                    let mut output_data = vec![0; encoded_size_upper_bound(input_data.len())];
+                    let len = cobs_encode_to_with::<$type>(&input_data, &mut output_data);
+                    output_data[..len] == cobs_encode_to_vec(&input_data)
+                }
+            });
+        };
+    }
+
+    next_zero_index_tests!(iter_position, IterPosition);
+    next_zero_index_tests!(swar, SimdBlocksSwar);
+    next_zero_index_tests!(simd16, SimdBlocks16);
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    next_zero_index_tests!(avx2, SimdBlocksAvx2);
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    next_zero_index_tests!(avx512, SimdBlocksAvx512);
+    #[cfg(target_arch = "aarch64")]
+    next_zero_index_tests!(neon, SimdBlocksNeon);
+    #[cfg(feature = "nightly")]
+    next_zero_index_tests!(std_simd_8, SimdBlocksGeneric<8>);
+    #[cfg(feature = "nightly")]
+    next_zero_index_tests!(std_simd_16, SimdBlocksGeneric<16>);
+    #[cfg(feature = "nightly")]
+    next_zero_index_tests!(std_simd_32, SimdBlocksGeneric<32>);
+    #[cfg(feature = "nightly")]
+    next_zero_index_tests!(std_simd_64, SimdBlocksGeneric<64>);
 
-    use std::{arch::x86_64::*, simd::*};
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
 
     #[test]
     fn iter() {
@@ -98,17 +322,17 @@ mod tests {
 
     use crate::next_zero_simd_128::SimdBlocks16;
 
-    use super::BlockIter;
+    use super::{BlockIter, GroupSplitter, Split};
+    #[cfg(target_arch = "x86_64")]
     #[test]
     fn simd() {
         let mut data = vec![27_u8; 1000];
         data[15] = 0;
-        let v = u8x16::from_slice(&data[0..16]);
         let res = unsafe {
             _mm_cmpestri(
                 _mm_setzero_si128(),
                 1,
-                __m128i::from(v),
+                _mm_loadu_si128(data.as_ptr() as *const __m128i),
                 16,
                 _SIDD_CMP_EQUAL_ORDERED,
             )
@@ -116,6 +340,46 @@ mod tests {
         dbg!(res);
     }
 
+    #[test]
+    #[should_panic(expected = "max_block_size must not be 0")]
+    fn zero_max_block_size() {
+        BlockIter::<SimdBlocks16>::new(&[1, 2, 3], 0);
+    }
+
+    #[quickcheck]
+    fn group_splitter_matches_block_iter(input_data: Vec<u8>, chunk_size: u8) -> bool {
+        let mut splitter = GroupSplitter::<SimdBlocks16>::new();
+        let mut groups = vec![];
+        let mut group = vec![];
+        for mut chunk in input_data.chunks(chunk_size as usize + 1) {
+            while let Some(split) = splitter.split(chunk) {
+                match split {
+                    Split::Zero { len, group_len } => {
+                        group.extend_from_slice(&chunk[..len]);
+                        if group.len() != group_len {
+                            return false;
+                        }
+                        groups.push(core::mem::take(&mut group));
+                        chunk = &chunk[len + 1..];
+                    }
+                    Split::Full { len } => {
+                        group.extend_from_slice(&chunk[..len]);
+                        groups.push(core::mem::take(&mut group));
+                        chunk = &chunk[len..];
+                    }
+                    Split::Open { len } => {
+                        group.extend_from_slice(&chunk[..len]);
+                        chunk = &chunk[len..];
+                    }
+                }
+            }
+        }
+        if splitter.group_open() {
+            groups.push(group);
+        }
+        BlockIter::<IterPosition>::new(&input_data, 254).eq(groups.iter().map(|g| &g[..]))
+    }
+
     #[quickcheck]
     fn max_block_size(input_data: Vec<u8>) -> bool {
         for b in BlockIter::<SimdBlocks16>::new(&input_data, 254) {
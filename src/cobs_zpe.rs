@@ -0,0 +1,228 @@
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::{block_iter::BlockIter, strip_delimiter, DecodeError, DefaultBlocks};
+
+/// Maximum number of data bytes in a group without trailing zero (code 0xE0)
+const MAX_BLOCK_SIZE: usize = 0xE0 - 1;
+/// First code of groups followed by a pair of zeros, 0xE1 is 0 data bytes followed by 00 00
+const PAIR_CODE: u8 = 0xE1;
+/// Maximum number of data bytes in a group followed by a pair of zeros (code 0xFF)
+const MAX_PAIR_BLOCK_SIZE: usize = (0xFF - PAIR_CODE) as usize;
+
+/// Calculates the maximum COBS/ZPE-encoded message size
+///
+/// There is at most one code byte for every 223 data bytes, plus one for the final group.
+pub fn cobs_zpe_encoded_size_upper_bound(input_size: usize) -> usize {
+    input_size + input_size / MAX_BLOCK_SIZE + 1
+}
+
+/// COBS/ZPE-encode data to a buffer.
+///
+/// COBS/ZPE (zero pair elimination) from the original COBS paper encodes groups of up to 30 bytes followed by
+/// a pair of zeros with a single code byte (0xE1 to 0xFF). In turn, groups without pair are limited to 222 bytes
+/// followed by a zero (0x01 to 0xDF) or 223 bytes without zero (0xE0).
+/// User must ensure that the buffer is big enough, the output always fits within [cobs_zpe_encoded_size_upper_bound].
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::cobs_zpe_encode_to;
+///
+/// let mut encoded_output = [0; 8];
+/// assert_eq!(cobs_zpe_encode_to(&[1, 3, 0, 0, 7, 0, 8], &mut encoded_output), 7);
+/// assert_eq!(encoded_output[..7], [0xE3, 1, 3, 2, 7, 2, 8]);
+/// ```
+pub fn cobs_zpe_encode_to(input: &[u8], output: &mut [u8]) -> usize {
+    let mut out_idx = 0;
+    // Blocks shorter than the maximum are terminated by a zero, the last one by the appended zero
+    let mut blocks = BlockIter::<DefaultBlocks>::new(input, MAX_BLOCK_SIZE).peekable();
+    while let Some(block) = blocks.next() {
+        output[out_idx] = if block.len() == MAX_BLOCK_SIZE {
+            0xE0
+        } else if block.len() <= MAX_PAIR_BLOCK_SIZE && blocks.next_if(|b| b.is_empty()).is_some() {
+            // Next block is empty, so this block is followed by two zeros
+            PAIR_CODE + block.len() as u8
+        } else {
+            block.len() as u8 + 1
+        };
+        // Copy all
+        output[out_idx + 1..out_idx + 1 + block.len()].copy_from_slice(block);
+        out_idx += block.len() + 1;
+    }
+
+    out_idx
+}
+
+/// COBS/ZPE-decode a frame to a buffer, rejecting malformed input.
+///
+/// The input may end with a single frame delimiter, any other zero byte is reported as error.
+/// Since a single code byte may stand for two zeros, the decoded data can be up to twice as long as the input.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::try_cobs_zpe_decode_to;
+///
+/// let mut decoded_output = [0; 8];
+/// assert_eq!(try_cobs_zpe_decode_to(&[0xE3, 1, 3, 2, 7, 2, 8, 0], &mut decoded_output), Ok(7));
+/// assert_eq!(decoded_output[..7], [1, 3, 0, 0, 7, 0, 8]);
+/// ```
+pub fn try_cobs_zpe_decode_to(input: &[u8], output: &mut [u8]) -> Result<usize, DecodeError> {
+    let input = strip_delimiter::<DefaultBlocks>(input, 0)?;
+
+    let mut in_idx = 0;
+    let mut out_idx = 0;
+    while in_idx < input.len() {
+        let code = input[in_idx];
+        let (block_len, zeros): (usize, usize) = match code {
+            0xE0 => (MAX_BLOCK_SIZE, 0),
+            PAIR_CODE..=0xFF => ((code - PAIR_CODE) as usize, 2),
+            _ => (code as usize - 1, 1),
+        };
+        let available = input.len() - in_idx - 1;
+        if block_len > available {
+            return Err(DecodeError::TruncatedGroup {
+                offset: in_idx,
+                expected: block_len,
+                available,
+            });
+        }
+
+        let block = &input[in_idx + 1..in_idx + 1 + block_len];
+        // Copy all
+        output
+            .get_mut(out_idx..out_idx + block.len())
+            .ok_or(DecodeError::OutputTooSmall)?
+            .copy_from_slice(block);
+        out_idx += block.len();
+        in_idx += block_len + 1;
+
+        // The zero appended by the encoder is removed again
+        let zeros = if in_idx < input.len() {
+            zeros
+        } else {
+            zeros.saturating_sub(1)
+        };
+        output
+            .get_mut(out_idx..out_idx + zeros)
+            .ok_or(DecodeError::OutputTooSmall)?
+            .fill(0);
+        out_idx += zeros;
+    }
+
+    Ok(out_idx)
+}
+
+/// COBS/ZPE-decode a frame to a newly allocated vector, rejecting malformed input.
+///
+/// See [try_cobs_zpe_decode_to] for details.
+#[cfg(feature = "alloc")]
+pub fn try_cobs_zpe_decode(input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let mut res = vec![0; 2 * input.len()];
+    let len = try_cobs_zpe_decode_to(input, &mut res)?;
+    res.truncate(len);
+    Ok(res)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::{cobs_zpe_encode_to, cobs_zpe_encoded_size_upper_bound, try_cobs_zpe_decode};
+    use crate::{cobs_encode_to_vec, DecodeError};
+
+    fn cobs_zpe_encode(input: &[u8]) -> Vec<u8> {
+        let mut output = vec![0; cobs_zpe_encoded_size_upper_bound(input.len())];
+        let len = cobs_zpe_encode_to(input, &mut output);
+        output.truncate(len);
+        output
+    }
+
+    #[test]
+    fn vectors() {
+        let vectors: [(&[u8], &[u8]); 9] = [
+            (&[], &[0x01]),
+            (&[0x00], &[0xE1]),
+            (&[0x00, 0x00], &[0xE1, 0x01]),
+            (&[0x00, 0x00, 0x00], &[0xE1, 0xE1]),
+            (&[0x11, 0x00], &[0xE2, 0x11]),
+            (&[0x11, 0x00, 0x22], &[0x02, 0x11, 0x02, 0x22]),
+            (&[0x11, 0x00, 0x00, 0x22], &[0xE2, 0x11, 0x02, 0x22]),
+            (&[0x11, 0x22, 0x00, 0x00, 0x00], &[0xE3, 0x11, 0x22, 0xE1]),
+            (&[0x00, 0x11, 0x00, 0x00], &[0x01, 0xE2, 0x11, 0x01]),
+        ];
+        for (raw, encoded) in vectors {
+            assert_eq!(cobs_zpe_encode(raw), encoded);
+            assert_eq!(try_cobs_zpe_decode(encoded), Ok(raw.to_vec()));
+        }
+    }
+
+    #[test]
+    fn long_groups() {
+        let mut raw = vec![0x11; 223];
+        let mut encoded = vec![0xE0];
+        encoded.extend_from_slice(&raw);
+        assert_eq!(cobs_zpe_encode(&raw), encoded);
+
+        // Zero after a group without trailing zero, paired with the appended zero
+        raw.push(0);
+        encoded.push(0xE1);
+        assert_eq!(cobs_zpe_encode(&raw), encoded);
+        assert_eq!(try_cobs_zpe_decode(&encoded), Ok(raw));
+
+        // Too long for a pair code
+        let mut raw = vec![0x11; 31];
+        raw.extend_from_slice(&[0, 0]);
+        let encoded = cobs_zpe_encode(&raw);
+        assert_eq!(encoded[0], 32);
+        assert_eq!(encoded[32..], [0xE1]);
+        assert_eq!(try_cobs_zpe_decode(&encoded), Ok(raw));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            try_cobs_zpe_decode(&[0x02, 0x00, 0x11]),
+            Err(DecodeError::ZeroInInput { offset: 1 })
+        );
+        assert_eq!(
+            try_cobs_zpe_decode(&[0xE3, 0x11]),
+            Err(DecodeError::TruncatedGroup {
+                offset: 0,
+                expected: 2,
+                available: 1
+            })
+        );
+    }
+
+    #[quickcheck]
+    fn roundtrip(input_data: Vec<u8>) -> bool {
+        let mut encoded = cobs_zpe_encode(&input_data);
+        encoded.push(0);
+        try_cobs_zpe_decode(&encoded) == Ok(input_data)
+    }
+
+    #[quickcheck]
+    fn roundtrip_zero_heavy(input_data: Vec<u8>) -> bool {
+        // Mostly zeros, to get many pairs
+        let input_data: Vec<_> = input_data.iter().map(|b| b.saturating_sub(200)).collect();
+        let encoded = cobs_zpe_encode(&input_data);
+        encoded.len() <= cobs_encode_to_vec(&input_data).len()
+            && try_cobs_zpe_decode(&encoded) == Ok(input_data)
+    }
+
+    #[quickcheck]
+    fn within_upper_bound(input_data: Vec<u8>, run_length: u8) -> bool {
+        // Long runs without zeros
+        let input_data: Vec<_> = input_data
+            .iter()
+            .flat_map(|b| vec![b | 1; run_length as usize])
+            .collect();
+        cobs_zpe_encode(&input_data).len() <= cobs_zpe_encoded_size_upper_bound(input_data.len())
+    }
+
+    #[quickcheck]
+    fn arbitrary_input_does_not_panic(input_data: Vec<u8>) -> bool {
+        let _ = try_cobs_zpe_decode(&input_data);
+        true
+    }
+}
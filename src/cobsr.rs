@@ -0,0 +1,188 @@
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::{block_iter::BlockIter, strip_delimiter, DecodeError, DefaultBlocks};
+
+/// COBS/R-encode data to a buffer.
+///
+/// COBS/R (Reduced) replaces the final overhead byte with the last data byte if that is at least as large,
+/// which often saves one byte per frame. Apart from that, the output is identical to COBS.
+/// User must ensure that the buffer is big enough, the output always fits within
+/// [encoded_size_upper_bound](crate::encoded_size_upper_bound)(input.len()).
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::cobsr_encode_to;
+///
+/// let mut encoded_output = [0; 8];
+/// assert_eq!(cobsr_encode_to(&[1, 3, 0, 7, 0, 8], &mut encoded_output), 6);
+/// assert_eq!(encoded_output[..6], [3, 1, 3, 2, 7, 8]);
+/// ```
+pub fn cobsr_encode_to(input: &[u8], output: &mut [u8]) -> usize {
+    let mut out_idx = 0;
+    let mut code_idx = 0;
+    for block in BlockIter::<DefaultBlocks>::new(input, 254) {
+        code_idx = out_idx;
+        output[out_idx] = block.len() as u8 + 1;
+        // Copy all
+        output[out_idx + 1..out_idx + 1 + block.len()].copy_from_slice(block);
+        out_idx += block.len() + 1;
+    }
+
+    let last_byte = output[out_idx - 1];
+    if out_idx - code_idx > 1 && last_byte >= output[code_idx] {
+        // Last data byte is moved to the overhead byte of the final group
+        output[code_idx] = last_byte;
+        out_idx -= 1;
+    }
+
+    out_idx
+}
+
+/// COBS/R-decode a frame to a buffer, rejecting malformed input.
+///
+/// The input may end with a single frame delimiter, any other zero byte is reported as error.
+/// A final group which is shorter than its overhead byte announces is a reduced group,
+/// so in contrast to COBS the decoded data may be as long as the input.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::try_cobsr_decode_to;
+///
+/// let mut decoded_output = [0; 8];
+/// assert_eq!(try_cobsr_decode_to(&[3, 1, 3, 2, 7, 8, 0], &mut decoded_output), Ok(6));
+/// assert_eq!(decoded_output[..6], [1, 3, 0, 7, 0, 8]);
+/// ```
+pub fn try_cobsr_decode_to(input: &[u8], output: &mut [u8]) -> Result<usize, DecodeError> {
+    let input = strip_delimiter::<DefaultBlocks>(input, 0)?;
+
+    let mut in_idx = 0;
+    let mut out_idx = 0;
+    while in_idx < input.len() {
+        let code = input[in_idx] as usize;
+        let available = input.len() - in_idx - 1;
+        let block = &input[in_idx + 1..in_idx + 1 + (code - 1).min(available)];
+        output
+            .get_mut(out_idx..out_idx + block.len())
+            .ok_or(DecodeError::OutputTooSmall)?
+            .copy_from_slice(block);
+        out_idx += block.len();
+        in_idx += code;
+
+        if code - 1 > available {
+            // Reduced final group, the overhead byte is the last data byte
+            *output.get_mut(out_idx).ok_or(DecodeError::OutputTooSmall)? = code as u8;
+            out_idx += 1;
+        } else if code != 255 && in_idx < input.len() {
+            // Group was terminated by a zero in the original data
+            *output.get_mut(out_idx).ok_or(DecodeError::OutputTooSmall)? = 0;
+            out_idx += 1;
+        }
+    }
+
+    Ok(out_idx)
+}
+
+/// COBS/R-decode a frame to a newly allocated vector, rejecting malformed input.
+///
+/// See [try_cobsr_decode_to] for details.
+#[cfg(feature = "alloc")]
+pub fn try_cobsr_decode(input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let mut res = vec![0; input.len()];
+    let len = try_cobsr_decode_to(input, &mut res)?;
+    res.truncate(len);
+    Ok(res)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::{cobsr_encode_to, try_cobsr_decode};
+    use crate::{cobs_encode_to_vec, encoded_size_upper_bound, DecodeError};
+
+    fn cobsr_encode(input: &[u8]) -> Vec<u8> {
+        let mut output = vec![0; encoded_size_upper_bound(input.len())];
+        let len = cobsr_encode_to(input, &mut output);
+        output.truncate(len);
+        output
+    }
+
+    #[test]
+    fn published_examples() {
+        // Last data byte smaller than the final overhead byte, same as COBS
+        let raw = [0x2F, 0xA2, 0x00, 0x92, 0x73, 0x02];
+        let encoded = [0x03, 0x2F, 0xA2, 0x04, 0x92, 0x73, 0x02];
+        assert_eq!(cobsr_encode(&raw), encoded);
+        assert_eq!(try_cobsr_decode(&encoded), Ok(raw.to_vec()));
+
+        // Last data byte replaces the final overhead byte
+        let raw = [0x2F, 0xA2, 0x00, 0x92, 0x73, 0x26];
+        let encoded = [0x03, 0x2F, 0xA2, 0x26, 0x92, 0x73];
+        assert_eq!(cobsr_encode(&raw), encoded);
+        assert_eq!(try_cobsr_decode(&encoded), Ok(raw.to_vec()));
+    }
+
+    #[test]
+    fn edge_cases() {
+        let vectors: [(&[u8], &[u8]); 8] = [
+            (&[], &[0x01]),
+            (&[0x00], &[0x01, 0x01]),
+            (&[0x01], &[0x02, 0x01]),
+            (&[0x02], &[0x02]),
+            (&[0xFF], &[0xFF]),
+            (&[0x00, 0x02], &[0x01, 0x02]),
+            (b"12345", b"51234"),
+            (b"12345\x00\x04", b"\x0612345\x04"),
+        ];
+        for (raw, encoded) in vectors {
+            assert_eq!(cobsr_encode(raw), encoded);
+            assert_eq!(try_cobsr_decode(encoded), Ok(raw.to_vec()));
+        }
+    }
+
+    #[test]
+    fn full_group_at_end() {
+        // Final overhead byte 0xFF can only be replaced by 0xFF
+        let mut raw = vec![0x01; 254];
+        assert_eq!(cobsr_encode(&raw), cobs_encode_to_vec(&raw));
+        raw[253] = 0xFF;
+        let encoded = cobsr_encode(&raw);
+        assert_eq!(encoded.len(), 254);
+        assert_eq!(encoded[0], 0xFF);
+        assert_eq!(try_cobsr_decode(&encoded), Ok(raw));
+    }
+
+    #[test]
+    fn zero_in_input() {
+        assert_eq!(
+            try_cobsr_decode(&[0x03, 0x00, 0x02]),
+            Err(DecodeError::ZeroInInput { offset: 1 })
+        );
+    }
+
+    #[quickcheck]
+    fn roundtrip(input_data: Vec<u8>) -> bool {
+        let mut encoded = cobsr_encode(&input_data);
+        encoded.push(0);
+        try_cobsr_decode(&encoded) == Ok(input_data)
+    }
+
+    #[quickcheck]
+    fn at_most_one_byte_shorter_than_cobs(input_data: Vec<u8>) -> bool {
+        let cobs = cobs_encode_to_vec(&input_data);
+        let cobsr = cobsr_encode(&input_data);
+        cobsr == cobs || cobsr.len() == cobs.len() - 1
+    }
+
+    #[quickcheck]
+    fn decodes_cobs(input_data: Vec<u8>) -> bool {
+        try_cobsr_decode(&cobs_encode_to_vec(&input_data)) == Ok(input_data)
+    }
+
+    #[quickcheck]
+    fn arbitrary_input_does_not_panic(input_data: Vec<u8>) -> bool {
+        let _ = try_cobsr_decode(&input_data);
+        true
+    }
+}
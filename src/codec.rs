@@ -0,0 +1,347 @@
+use std::io;
+
+use bytes::{Buf, BytesMut};
+
+use crate::{
+    cobs_decode_in_place, cobs_encode_to, encoded_size_upper_bound,
+    frame_splitter::{FrameSplit, FrameSplitter},
+    Method,
+};
+
+/// COBS codec for `tokio_util::codec` (`tokio` feature) and `asynchronous_codec` (`asynchronous-codec` feature)
+///
+/// Frames are encoded directly into the output buffer and decoded in place, so decoding does not allocate.
+/// Delimiters without data in between, e.g. sent to resynchronise the receiver, yield no item.
+/// Malformed frames are reported as [io::ErrorKind::InvalidData], decoding resumes after the next delimiter.
+/// Data after the last delimiter at the end of the stream is reported as [io::ErrorKind::UnexpectedEof].
+/// Both integrations share the same implementation, and the frame splitting of [CobsReader](crate::CobsReader),
+/// so they behave identically. Only the `FramedRead` streams differ after an error:
+/// the one of `tokio_util` returns `None` once and continues when polled again,
+/// the one of `asynchronous_codec` continues right away.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "tokio")]
+/// # {
+/// use bytes::BytesMut;
+/// use cobs_simd::{CobsCodec, Method};
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let mut codec = CobsCodec::new(Method::Auto);
+/// let mut buf = BytesMut::new();
+/// codec.encode(&[1, 3, 0, 7][..], &mut buf)?;
+/// assert_eq!(buf[..], [3, 1, 3, 2, 7, 0]);
+/// assert_eq!(codec.decode(&mut buf)?.unwrap()[..], [1, 3, 0, 7]);
+/// # }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct CobsCodec {
+    method: Method,
+    splitter: FrameSplitter, // Encoded data of the current frame is kept at the start of the buffer
+}
+
+impl CobsCodec {
+    /// Creates a codec encoding with `method`, without limit on the frame length.
+    pub fn new(method: Method) -> CobsCodec {
+        CobsCodec::new_with_max_length(method, usize::MAX)
+    }
+
+    /// Creates a codec encoding with `method`, which rejects frames longer than `max_frame_length` while decoding.
+    ///
+    /// The length refers to the encoded frame without delimiter, which limits the amount of buffered data.
+    /// A frame exceeding it is reported as [io::ErrorKind::InvalidData] as soon as it is detected,
+    /// and its remaining data is discarded up to the next delimiter.
+    pub fn new_with_max_length(method: Method, max_frame_length: usize) -> CobsCodec {
+        CobsCodec {
+            method,
+            splitter: FrameSplitter::new(max_frame_length),
+        }
+    }
+
+    /// Maximum length of an encoded frame accepted while decoding, `usize::MAX` for [CobsCodec::new].
+    pub fn max_frame_length(&self) -> usize {
+        self.splitter.max_frame_length()
+    }
+
+    fn decode_frame(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        loop {
+            let pending = self.splitter.pending();
+            match self.splitter.split(&src[pending..]) {
+                FrameSplit::Incomplete => return Ok(None),
+                FrameSplit::Frame { end } => {
+                    let mut frame = src.split_to(pending + end + 1);
+                    let len = cobs_decode_in_place(&mut frame)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                    frame.truncate(len);
+                    return Ok(Some(frame));
+                }
+                FrameSplit::Skipped(n) => src.advance(pending + n),
+                FrameSplit::TooLong(n) => {
+                    src.advance(pending + n);
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "frame exceeds maximum frame length",
+                    ));
+                }
+            }
+        }
+    }
+
+    fn decode_frame_eof(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        if let Some(frame) = self.decode_frame(src)? {
+            return Ok(Some(frame));
+        }
+        if self.splitter.finish() {
+            src.clear();
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "stream ended within a frame",
+            ));
+        }
+        Ok(None)
+    }
+
+    fn encode_frame(&self, item: &[u8], dst: &mut BytesMut) {
+        let start = dst.len();
+        dst.resize(start + encoded_size_upper_bound(item.len()) + 1, 0);
+        let len = cobs_encode_to(item, &mut dst[start..], self.method.clone());
+        dst[start + len] = 0;
+        dst.truncate(start + len + 1);
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio_util::codec::Decoder for CobsCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        self.decode_frame(src)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        self.decode_frame_eof(src)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio_util::codec::Encoder<&[u8]> for CobsCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: &[u8], dst: &mut BytesMut) -> io::Result<()> {
+        self.encode_frame(item, dst);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio_util::codec::Encoder<bytes::Bytes> for CobsCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: bytes::Bytes, dst: &mut BytesMut) -> io::Result<()> {
+        self.encode_frame(&item, dst);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "asynchronous-codec")]
+impl asynchronous_codec::Decoder for CobsCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        self.decode_frame(src)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        self.decode_frame_eof(src)
+    }
+}
+
+#[cfg(feature = "asynchronous-codec")]
+impl asynchronous_codec::Encoder for CobsCodec {
+    type Item<'a> = &'a [u8];
+    type Error = io::Error;
+
+    fn encode(&mut self, item: &[u8], dst: &mut BytesMut) -> io::Result<()> {
+        self.encode_frame(item, dst);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use bytes::BytesMut;
+    use strum::IntoEnumIterator;
+
+    use super::CobsCodec;
+    use crate::{cobs_encode_to_vec, Method};
+
+    fn frames() -> Vec<Vec<u8>> {
+        vec![
+            vec![0x11],
+            vec![0x00],
+            (0..=255).collect(),
+            vec![0x22; 1000],
+            (0..5000).map(|i| (i % 7) as u8).collect(),
+            vec![],
+            vec![0x33, 0x00],
+        ]
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn tokio_duplex() {
+        use futures::{SinkExt, StreamExt};
+        use tokio_util::codec::{FramedRead, FramedWrite};
+
+        // Small pipe, so frames are split across reads
+        let (client, server) = tokio::io::duplex(64);
+        let mut sink = FramedWrite::new(client, CobsCodec::new(Method::Auto));
+        let mut stream = FramedRead::new(server, CobsCodec::new(Method::Auto));
+
+        let sender = tokio::spawn(async move {
+            for (i, frame) in frames().into_iter().enumerate() {
+                if i % 2 == 0 {
+                    sink.send(bytes::Bytes::from(frame)).await.unwrap();
+                } else {
+                    sink.send(&frame[..]).await.unwrap();
+                }
+            }
+        });
+
+        for frame in frames() {
+            assert_eq!(stream.next().await.unwrap().unwrap(), frame);
+        }
+        sender.await.unwrap();
+        assert!(stream.next().await.is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn tokio_stream_resumes_after_invalid_frame() {
+        use futures::StreamExt;
+        use tokio_util::codec::FramedRead;
+
+        let stream: &[u8] = &[0x02, 0x11, 0x00, 0x05, 0x11, 0x00, 0x02, 0x22, 0x00];
+        let mut frames = FramedRead::new(stream, CobsCodec::new(Method::Auto));
+        assert_eq!(frames.next().await.unwrap().unwrap()[..], [0x11]);
+        assert_eq!(
+            frames.next().await.unwrap().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        // FramedRead ends the stream once after an error
+        assert!(frames.next().await.is_none());
+        assert_eq!(frames.next().await.unwrap().unwrap()[..], [0x22]);
+        assert!(frames.next().await.is_none());
+    }
+
+    #[cfg(feature = "asynchronous-codec")]
+    #[test]
+    fn asynchronous_codec_roundtrip() {
+        use asynchronous_codec::{FramedRead, FramedWrite};
+        use futures::{executor::block_on, io::Cursor, SinkExt, TryStreamExt};
+
+        let mut sink = FramedWrite::new(Vec::new(), CobsCodec::new(Method::Auto));
+        for frame in frames() {
+            block_on(sink.send(&frame[..])).unwrap();
+        }
+        let stream = sink.into_inner();
+
+        let decoded: Vec<_> = block_on(
+            FramedRead::new(Cursor::new(stream), CobsCodec::new(Method::Auto)).try_collect(),
+        )
+        .unwrap();
+        assert_eq!(decoded, frames());
+    }
+
+    #[cfg(feature = "asynchronous-codec")]
+    #[test]
+    fn asynchronous_codec_errors() {
+        use asynchronous_codec::FramedRead;
+        use futures::{executor::block_on, io::Cursor, StreamExt};
+
+        let stream = Cursor::new(vec![0x05, 0x11, 0x00, 0x02, 0x11, 0x00, 0x02, 0x22]);
+        let mut frames = FramedRead::new(stream, CobsCodec::new(Method::Auto));
+        assert_eq!(
+            block_on(frames.next()).unwrap().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(block_on(frames.next()).unwrap().unwrap()[..], [0x11]);
+        assert_eq!(
+            block_on(frames.next()).unwrap().unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn encodes_all_methods() {
+        for frame in frames() {
+            let mut expected = cobs_encode_to_vec(&frame);
+            expected.push(0);
+            for method in Method::iter() {
+                let mut buf = BytesMut::from(&b"prefix"[..]);
+                CobsCodec::new(method).encode_frame(&frame, &mut buf);
+                assert_eq!(buf[..6], *b"prefix");
+                assert_eq!(buf[6..], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn partial_frame() {
+        let mut codec = CobsCodec::new(Method::Auto);
+        let mut buf = BytesMut::from(&[0x00, 0x03, 0x11][..]);
+        assert!(codec.decode_frame(&mut buf).unwrap().is_none());
+        assert_eq!(buf[..], [0x03, 0x11]);
+        buf.extend_from_slice(&[0x22, 0x00, 0x02]);
+        assert_eq!(
+            codec.decode_frame(&mut buf).unwrap().unwrap()[..],
+            [0x11, 0x22]
+        );
+        assert!(codec.decode_frame(&mut buf).unwrap().is_none());
+        assert_eq!(buf[..], [0x02]);
+        assert_eq!(
+            codec.decode_frame_eof(&mut buf).unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+        assert!(buf.is_empty());
+        assert!(codec.decode_frame_eof(&mut buf).unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_frame() {
+        let mut codec = CobsCodec::new(Method::Auto);
+        let mut buf = BytesMut::from(&[0x05, 0x11, 0x00, 0x02, 0x22, 0x00][..]);
+        assert_eq!(
+            codec.decode_frame(&mut buf).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(buf[..], [0x02, 0x22, 0x00]);
+        assert_eq!(codec.decode_frame(&mut buf).unwrap().unwrap()[..], [0x22]);
+    }
+
+    #[test]
+    fn overlong_frame_leaves_buffer() {
+        let mut codec = CobsCodec::new_with_max_length(Method::Auto, 3);
+        assert_eq!(codec.max_frame_length(), 3);
+
+        // Overlong frame is dropped from the buffer before its delimiter arrives
+        let mut buf = BytesMut::from(&[0x06, 0x11, 0x22, 0x33][..]);
+        assert_eq!(
+            codec.decode_frame(&mut buf).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert!(buf.is_empty());
+        buf.extend_from_slice(&[0x44, 0x00, 0x02, 0x66]);
+        assert!(codec.decode_frame(&mut buf).unwrap().is_none());
+        assert_eq!(buf[..], [0x02, 0x66]);
+        buf.extend_from_slice(&[0x00]);
+        assert_eq!(codec.decode_frame(&mut buf).unwrap().unwrap()[..], [0x66]);
+        assert!(buf.is_empty());
+    }
+}
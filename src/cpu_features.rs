@@ -0,0 +1,130 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use strum_macros::{Display, EnumIter};
+
+/// Zero search backend used by [Method::Auto](crate::Method::Auto)
+///
+/// Ordered from least to most capable within each architecture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Display, EnumIter)]
+#[repr(u8)]
+pub enum Backend {
+    /// std::simd with 16 lanes with the `nightly` feature, lowered to whatever the compile-time target supports.
+    /// Word-at-a-time search (SWAR) otherwise.
+    Portable = 1,
+    /// SSE4.2 string instructions (`_mm_cmpestri`)
+    Sse42 = 2,
+    /// AVX2 intrinsics (`_mm256_cmpeq_epi8` and `_mm256_movemask_epi8`)
+    Avx2 = 3,
+    /// AVX-512BW intrinsics (`_mm512_cmpeq_epi8_mask`)
+    Avx512 = 4,
+    /// NEON intrinsics (`vceqq_u8` and shift-right-narrow bitmask), available on every aarch64 CPU
+    Neon = 5,
+}
+
+static DETECTED_BACKEND: AtomicU8 = AtomicU8::new(0);
+
+/// Returns the fastest backend supported by the CPU.
+///
+/// CPU features are detected on first use and cached afterwards.
+/// Without the `std` feature, only features enabled at compile time are considered.
+pub fn detected_backend() -> Backend {
+    match DETECTED_BACKEND.load(Ordering::Relaxed) {
+        1 => Backend::Portable,
+        2 => Backend::Sse42,
+        3 => Backend::Avx2,
+        4 => Backend::Avx512,
+        5 => Backend::Neon,
+        _ => {
+            let backend = detect_backend();
+            DETECTED_BACKEND.store(backend as u8, Ordering::Relaxed);
+            backend
+        }
+    }
+}
+
+/// Checks whether the backend can be used on this CPU
+pub fn backend_supported(backend: Backend) -> bool {
+    match backend {
+        Backend::Portable => true,
+        Backend::Sse42 => has_sse42(),
+        Backend::Avx2 => has_avx2(),
+        Backend::Avx512 => has_avx512bw(),
+        Backend::Neon => cfg!(target_arch = "aarch64"),
+    }
+}
+
+fn detect_backend() -> Backend {
+    if cfg!(target_arch = "aarch64") {
+        Backend::Neon
+    } else if has_avx512bw() {
+        Backend::Avx512
+    } else if has_avx2() {
+        Backend::Avx2
+    } else if has_sse42() {
+        Backend::Sse42
+    } else {
+        Backend::Portable
+    }
+}
+
+macro_rules! has_x86_feature {
+    ($feature:tt) => {{
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+        {
+            std::is_x86_feature_detected!($feature)
+        }
+        #[cfg(not(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std")))]
+        {
+            cfg!(target_feature = $feature)
+        }
+    }};
+}
+
+// Building with `--cfg cobs_force_fallback` disables the x86 intrinsics, to test the portable fallback on any CPU
+
+#[inline]
+pub(crate) fn has_sse42() -> bool {
+    !cfg!(cobs_force_fallback) && has_x86_feature!("sse4.2")
+}
+
+#[inline]
+pub(crate) fn has_avx2() -> bool {
+    !cfg!(cobs_force_fallback) && has_x86_feature!("avx2")
+}
+
+#[inline]
+pub(crate) fn has_avx512bw() -> bool {
+    !cfg!(cobs_force_fallback) && has_x86_feature!("avx512bw")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backend_supported, detected_backend, Backend};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn detected_backend_is_supported() {
+        let backend = detected_backend();
+        assert!(backend_supported(backend));
+        assert_eq!(detected_backend(), backend);
+    }
+
+    #[cfg(cobs_force_fallback)]
+    #[test]
+    fn forced_fallback_reported() {
+        for backend in [Backend::Sse42, Backend::Avx2, Backend::Avx512] {
+            assert!(!backend_supported(backend), "{backend} supported");
+        }
+    }
+
+    #[test]
+    fn detected_backend_is_best() {
+        let backend = detected_backend();
+        for other in Backend::iter().filter(|b| *b > backend) {
+            assert!(
+                !backend_supported(other),
+                "{other} supported, but {backend} detected"
+            );
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use alloc::{collections::VecDeque, vec::Vec};
+use core::{cmp::min, mem};
+
+use crate::{block_iter::NextZeroIndex, DecodeError, DefaultBlocks};
+
+/// Incremental COBS decoder
+///
+/// Accepts encoded data in arbitrary chunks and yields the decoded frames on each zero delimiter.
+/// Group boundaries are tracked across calls to [CobsDecoder::feed], so frames may be split anywhere.
+/// A delimiter directly following another one completes no frame, so senders may emit extra delimiters to resynchronise.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::CobsDecoder;
+///
+/// let mut decoder = CobsDecoder::new();
+/// assert_eq!(decoder.feed(&[3, 1, 3, 2]).count(), 0);
+/// let frames: Vec<_> = decoder.feed(&[7, 0, 2, 8, 0]).collect();
+/// assert_eq!(frames, [Ok(vec![1, 3, 0, 7]), Ok(vec![8])]);
+/// ```
+#[derive(Default)]
+pub struct CobsDecoder {
+    frame: Vec<u8>,             // Decoded data of the current frame
+    frame_length: usize,        // Number of encoded bytes of the current frame
+    group_offset: usize,        // Offset of the current overhead byte within the frame
+    remaining_group_length: u8, // Data bytes missing from the current group, 0 if next byte is an overhead byte
+    previous_code: u8,          // Overhead byte of the current group, 0 before the first group
+    // Frames not yet taken from a [Frames] iterator
+    completed: VecDeque<Result<Vec<u8>, DecodeError>>,
+}
+
+impl CobsDecoder {
+    pub fn new() -> CobsDecoder {
+        Default::default()
+    }
+
+    /// Feeds encoded data into the decoder.
+    ///
+    /// All of `data` is decoded right away, the returned iterator yields every frame completed by a delimiter.
+    /// Malformed frames are reported as error, decoding resumes after the next delimiter.
+    /// Frames not taken from the iterator are kept, and yielded first by the iterator of the next call.
+    #[must_use = "decoded frames are kept until taken from the iterator"]
+    pub fn feed(&mut self, mut data: &[u8]) -> Frames<'_> {
+        while !data.is_empty() {
+            if let Some(frame) = self.step(&mut data) {
+                self.completed.push_back(frame);
+            }
+        }
+        Frames { decoder: self }
+    }
+
+    /// Discards the partially received frame.
+    ///
+    /// Completed frames not yet taken from a [Frames] iterator are kept.
+    pub fn reset(&mut self) {
+        self.frame.clear();
+        self.frame_length = 0;
+        self.group_offset = 0;
+        self.remaining_group_length = 0;
+        self.previous_code = 0;
+    }
+
+    /// Processes either the data of the current group or the next overhead byte
+    fn step(&mut self, data: &mut &[u8]) -> Option<Result<Vec<u8>, DecodeError>> {
+        if self.remaining_group_length > 0 {
+            let available = min(self.remaining_group_length as usize, data.len());
+            let group_data = &data[..available];
+
+            if let Some(i) = DefaultBlocks::next_zero_index(group_data) {
+                // Delimiter before the end of the group
+                let expected = self.previous_code as usize - 1;
+                let error = DecodeError::TruncatedGroup {
+                    offset: self.group_offset,
+                    expected,
+                    available: expected - self.remaining_group_length as usize + i,
+                };
+                *data = &data[i + 1..];
+                self.reset();
+                return Some(Err(error));
+            }
+
+            self.frame.extend_from_slice(group_data);
+            self.frame_length += available;
+            self.remaining_group_length -= available as u8;
+            *data = &data[available..];
+            return None;
+        }
+
+        let code = data[0];
+        *data = &data[1..];
+
+        if code == 0 {
+            if self.frame_length == 0 {
+                // Empty frame
+                return None;
+            }
+            let frame = mem::take(&mut self.frame);
+            self.reset();
+            return Some(Ok(frame));
+        }
+
+        if self.previous_code != 0 && self.previous_code != 255 {
+            // Previous group ended with a zero
+            self.frame.push(0);
+        }
+        self.group_offset = self.frame_length;
+        self.frame_length += 1;
+        self.previous_code = code;
+        self.remaining_group_length = code - 1;
+        None
+    }
+}
+
+/// Iterator over the completed frames of a [CobsDecoder], see [CobsDecoder::feed]
+pub struct Frames<'a> {
+    decoder: &'a mut CobsDecoder,
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<Vec<u8>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.decoder.completed.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CobsDecoder;
+    use crate::{cobs_encode_to_vec, DecodeError};
+
+    #[quickcheck]
+    fn chunked_frames(frames: Vec<Vec<u8>>, chunk_size: u8) -> bool {
+        let mut stream = vec![];
+        for frame in &frames {
+            stream.extend(cobs_encode_to_vec(frame));
+            stream.push(0);
+        }
+
+        let mut decoder = CobsDecoder::new();
+        let mut decoded = vec![];
+        for chunk in stream.chunks(chunk_size as usize + 1) {
+            decoded.extend(decoder.feed(chunk).map(Result::unwrap));
+        }
+        decoded == frames
+    }
+
+    #[test]
+    fn byte_at_a_time() {
+        let mut decoder = CobsDecoder::new();
+        let mut decoded = vec![];
+        for b in [0x03, 0x11, 0x22, 0x02, 0x33, 0x00] {
+            decoded.extend(decoder.feed(&[b]));
+        }
+        assert_eq!(decoded, [Ok(vec![0x11, 0x22, 0x00, 0x33])]);
+    }
+
+    #[test]
+    fn long_groups() {
+        let frame: Vec<_> = (0..1000).map(|i| (i % 255) as u8 + 1).collect();
+        let mut stream = cobs_encode_to_vec(&frame);
+        stream.push(0);
+
+        let mut decoder = CobsDecoder::new();
+        let decoded: Vec<_> = decoder.feed(&stream).collect();
+        assert_eq!(decoded, [Ok(frame)]);
+    }
+
+    #[test]
+    fn empty_frames_are_skipped() {
+        let mut decoder = CobsDecoder::new();
+        let decoded: Vec<_> = decoder
+            .feed(&[0x00, 0x00, 0x02, 0x11, 0x00, 0x00])
+            .collect();
+        assert_eq!(decoded, [Ok(vec![0x11])]);
+    }
+
+    #[test]
+    fn truncated_frame_resynchronizes() {
+        let mut decoder = CobsDecoder::new();
+        let decoded: Vec<_> = decoder
+            .feed(&[0x03, 0x11, 0x22, 0x05, 0x33, 0x00, 0x02, 0x44, 0x00])
+            .collect();
+        assert_eq!(
+            decoded,
+            [
+                Err(DecodeError::TruncatedGroup {
+                    offset: 3,
+                    expected: 4,
+                    available: 1
+                }),
+                Ok(vec![0x44])
+            ]
+        );
+    }
+
+    #[test]
+    fn frames_kept_when_iterator_dropped() {
+        let mut decoder = CobsDecoder::new();
+        let first = decoder
+            .feed(&[0x02, 0x11, 0x00, 0x02, 0x22, 0x00, 0x02])
+            .next();
+        assert_eq!(first, Some(Ok(vec![0x11])));
+        let decoded: Vec<_> = decoder.feed(&[0x33, 0x00]).collect();
+        assert_eq!(decoded, [Ok(vec![0x22]), Ok(vec![0x33])]);
+    }
+
+    #[test]
+    fn reset_discards_partial_frame() {
+        let mut decoder = CobsDecoder::new();
+        assert_eq!(decoder.feed(&[0x05, 0x11, 0x22]).count(), 0);
+        decoder.reset();
+        let decoded: Vec<_> = decoder.feed(&[0x02, 0x44, 0x00]).collect();
+        assert_eq!(decoded, [Ok(vec![0x44])]);
+    }
+}
@@ -0,0 +1,369 @@
+use core::fmt;
+
+use embedded_io::{Read, Write};
+
+use crate::{
+    cobs_decode_in_place,
+    frame_splitter::{FrameSplit, FrameSplitter},
+    try_cobs_encode_to, DecodeError, EncodeError, Method,
+};
+
+/// Errors of the `embedded-io` frame adapters, wrapping errors `E` of the underlying reader or writer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FrameError<E> {
+    /// The underlying reader or writer failed.
+    Io(E),
+    /// The encoded frame does not fit into the buffer of the writer.
+    Encode(EncodeError),
+    /// A received frame is malformed, or does not fit into the buffer of the reader ([DecodeError::OutputTooSmall]).
+    Decode(DecodeError),
+    /// The stream ended within a frame.
+    UnexpectedEof,
+}
+
+impl<E> From<EncodeError> for FrameError<E> {
+    fn from(e: EncodeError) -> Self {
+        FrameError::Encode(e)
+    }
+}
+
+impl<E> From<DecodeError> for FrameError<E> {
+    fn from(e: DecodeError) -> Self {
+        FrameError::Decode(e)
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for FrameError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Io(e) => write!(f, "I/O error: {e:?}"),
+            FrameError::Encode(e) => write!(f, "{e}"),
+            FrameError::Decode(e) => write!(f, "{e}"),
+            FrameError::UnexpectedEof => write!(f, "stream ended within a frame"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug> std::error::Error for FrameError<E> {}
+
+impl<E: embedded_io::Error> embedded_io::Error for FrameError<E> {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            FrameError::Io(e) => e.kind(),
+            FrameError::Encode(_) => embedded_io::ErrorKind::OutOfMemory,
+            FrameError::Decode(_) => embedded_io::ErrorKind::InvalidData,
+            FrameError::UnexpectedEof => embedded_io::ErrorKind::Other,
+        }
+    }
+}
+
+/// Encodes `frame` into `buf`, followed by the delimiter, returning the encoded data.
+pub(crate) fn encode_frame<'b>(
+    frame: &[u8],
+    buf: &'b mut [u8],
+    method: Method,
+) -> Result<&'b [u8], EncodeError> {
+    // Last byte is reserved for the delimiter
+    let encode_len = buf.len().saturating_sub(1);
+    let len = try_cobs_encode_to(frame, &mut buf[..encode_len], method).map_err(
+        |EncodeError::OutputTooSmall { required }| EncodeError::OutputTooSmall {
+            required: required + 1,
+        },
+    )?;
+    buf[len] = 0;
+    Ok(&buf[..len + 1])
+}
+
+/// Received data of the frame readers, frames are decoded in place
+pub(crate) struct FrameBuffer<'b> {
+    buf: &'b mut [u8],
+    len: usize,      // Bytes received
+    consumed: usize, // Bytes of the previously returned frame, removed before reading the next one
+    splitter: FrameSplitter,
+}
+
+impl<'b> FrameBuffer<'b> {
+    pub(crate) fn new(buf: &'b mut [u8]) -> FrameBuffer<'b> {
+        // Last byte is reserved for the delimiter
+        let max_frame_length = buf.len().saturating_sub(1);
+        FrameBuffer {
+            buf,
+            len: 0,
+            consumed: 0,
+            splitter: FrameSplitter::new(max_frame_length),
+        }
+    }
+
+    /// Decodes the next complete frame, returning its length, or `None` if more data is needed
+    pub(crate) fn decode_next(&mut self) -> Result<Option<usize>, DecodeError> {
+        let consumed = core::mem::take(&mut self.consumed);
+        self.discard(consumed);
+
+        loop {
+            let pending = self.splitter.pending();
+            match self.splitter.split(&self.buf[pending..self.len]) {
+                FrameSplit::Incomplete => return Ok(None),
+                FrameSplit::Frame { end } => {
+                    self.consumed = pending + end + 1;
+                    return cobs_decode_in_place(&mut self.buf[..self.consumed]).map(Some);
+                }
+                FrameSplit::Skipped(n) => self.discard(pending + n),
+                FrameSplit::TooLong(n) => {
+                    self.consumed = pending + n;
+                    return Err(DecodeError::OutputTooSmall);
+                }
+            }
+        }
+    }
+
+    fn discard(&mut self, n: usize) {
+        self.buf.copy_within(n..self.len, 0);
+        self.len -= n;
+    }
+
+    /// Space to read more data into
+    pub(crate) fn spare(&mut self) -> &mut [u8] {
+        &mut self.buf[self.len..]
+    }
+
+    pub(crate) fn fill(&mut self, n: usize) {
+        self.len += n;
+    }
+
+    /// Checks that the stream did not end within a frame
+    pub(crate) fn finish<E>(&mut self) -> Result<Option<&[u8]>, FrameError<E>> {
+        self.len = 0;
+        if self.splitter.finish() {
+            return Err(FrameError::UnexpectedEof);
+        }
+        Ok(None)
+    }
+
+    pub(crate) fn frame(&self, len: usize) -> &[u8] {
+        &self.buf[..len]
+    }
+}
+
+/// COBS frame writer for [embedded_io::Write], requires the `embedded-io` feature
+///
+/// Frames are encoded into a caller-provided buffer, which must hold the largest encoded frame plus the delimiter,
+/// see [encoded_size_upper_bound](crate::encoded_size_upper_bound).
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{CobsFrameWriter, Method};
+///
+/// let mut output = [0; 16];
+/// let mut buf = [0; 8];
+/// let mut writer = CobsFrameWriter::new(&mut output[..], &mut buf, Method::Auto);
+/// writer.write_frame(&[1, 3, 0, 7])?;
+/// writer.write_frame(&[8])?;
+/// assert_eq!(output[..8], [3, 1, 3, 2, 7, 0, 2, 8]);
+/// # Ok::<(), cobs_simd::FrameError<embedded_io::SliceWriteError>>(())
+/// ```
+pub struct CobsFrameWriter<'b, W: Write> {
+    inner: W,
+    buf: &'b mut [u8],
+    method: Method,
+}
+
+impl<'b, W: Write> CobsFrameWriter<'b, W> {
+    pub fn new(inner: W, buf: &'b mut [u8], method: Method) -> CobsFrameWriter<'b, W> {
+        CobsFrameWriter { inner, buf, method }
+    }
+
+    /// Encodes `frame` and writes it followed by the delimiter.
+    ///
+    /// Nothing is written if the encoded frame does not fit into the buffer.
+    pub fn write_frame(&mut self, frame: &[u8]) -> Result<(), FrameError<W::Error>> {
+        let encoded = encode_frame(frame, self.buf, self.method.clone())?;
+        self.inner.write_all(encoded).map_err(FrameError::Io)
+    }
+
+    pub fn flush(&mut self) -> Result<(), FrameError<W::Error>> {
+        self.inner.flush().map_err(FrameError::Io)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// COBS frame reader for [embedded_io::Read], requires the `embedded-io` feature
+///
+/// Received data is collected in a caller-provided buffer, and frames are decoded in place.
+/// The buffer must hold the largest encoded frame plus the delimiter, frames that do not fit are reported as
+/// [DecodeError::OutputTooSmall] and skipped. Data following a frame in the same read is kept for the next one.
+/// Consecutive delimiters are dropped from the buffer without returning a frame.
+/// Malformed frames are reported as error, reading resumes with the next frame afterwards.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::CobsFrameReader;
+///
+/// let mut buf = [0; 8];
+/// let mut reader = CobsFrameReader::new(&[3, 1, 3, 2, 7, 0, 2, 8, 0][..], &mut buf);
+/// assert_eq!(reader.read_frame()?, Some(&[1, 3, 0, 7][..]));
+/// assert_eq!(reader.read_frame()?, Some(&[8][..]));
+/// assert_eq!(reader.read_frame()?, None);
+/// # Ok::<(), cobs_simd::FrameError<core::convert::Infallible>>(())
+/// ```
+pub struct CobsFrameReader<'b, R: Read> {
+    inner: R,
+    buf: FrameBuffer<'b>,
+}
+
+impl<'b, R: Read> CobsFrameReader<'b, R> {
+    pub fn new(inner: R, buf: &'b mut [u8]) -> CobsFrameReader<'b, R> {
+        CobsFrameReader {
+            inner,
+            buf: FrameBuffer::new(buf),
+        }
+    }
+
+    /// Reads the next frame, returning the decoded data or `None` at the end of the stream.
+    pub fn read_frame(&mut self) -> Result<Option<&[u8]>, FrameError<R::Error>> {
+        loop {
+            if let Some(len) = self.buf.decode_next()? {
+                return Ok(Some(self.buf.frame(len)));
+            }
+            let n = self.inner.read(self.buf.spare()).map_err(FrameError::Io)?;
+            if n == 0 {
+                return self.buf.finish();
+            }
+            self.buf.fill(n);
+        }
+    }
+
+    /// Returns the underlying reader, discarding received data that was not returned yet.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_io::{ErrorType, Read};
+
+    use super::{CobsFrameReader, CobsFrameWriter, FrameError};
+    use crate::{cobs_encode_to_vec, encoded_size_upper_bound, DecodeError, EncodeError, Method};
+
+    /// Reader returning at most `chunk_size` bytes per call
+    struct ChunkedReader<'a> {
+        data: &'a [u8],
+        chunk_size: usize,
+    }
+
+    impl ErrorType for ChunkedReader<'_> {
+        type Error = core::convert::Infallible;
+    }
+
+    impl Read for ChunkedReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let n = self.data.len().min(buf.len()).min(self.chunk_size);
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    fn max_encoded_size(frames: &[Vec<u8>]) -> usize {
+        frames
+            .iter()
+            .map(|f| encoded_size_upper_bound(f.len()) + 1)
+            .max()
+            .unwrap_or(1)
+    }
+
+    #[quickcheck]
+    fn roundtrip(frames: Vec<Vec<u8>>, chunk_size: u8) -> bool {
+        let mut stream = vec![
+            0;
+            frames
+                .iter()
+                .map(|f| encoded_size_upper_bound(f.len()) + 1)
+                .sum()
+        ];
+        let mut buf = vec![0; max_encoded_size(&frames)];
+        let mut writer = CobsFrameWriter::new(&mut stream[..], &mut buf, Method::Auto);
+        for frame in &frames {
+            writer.write_frame(frame).unwrap();
+        }
+        let unused = writer.into_inner().len();
+        stream.truncate(stream.len() - unused);
+
+        let inner = ChunkedReader {
+            data: &stream,
+            chunk_size: chunk_size as usize + 1,
+        };
+        let mut reader = CobsFrameReader::new(inner, &mut buf);
+        frames
+            .iter()
+            .all(|f| reader.read_frame().unwrap() == Some(&f[..]))
+            && reader.read_frame().unwrap().is_none()
+    }
+
+    #[test]
+    fn writes_cobs() {
+        let frame = [0x11, 0x00, 0x22];
+        let mut expected = cobs_encode_to_vec(&frame);
+        expected.push(0);
+        let mut output = [0; 8];
+        let mut buf = [0; 5];
+        CobsFrameWriter::new(&mut output[..], &mut buf, Method::Auto)
+            .write_frame(&frame)
+            .unwrap();
+        assert_eq!(output[..5], expected);
+    }
+
+    #[test]
+    fn writer_buffer_too_small() {
+        let mut output = [0; 8];
+        let mut buf = [0; 4];
+        let mut writer = CobsFrameWriter::new(&mut output[..], &mut buf, Method::Auto);
+        assert_eq!(
+            writer.write_frame(&[0x11, 0x00, 0x22]),
+            Err(FrameError::Encode(EncodeError::OutputTooSmall {
+                required: 5
+            }))
+        );
+        assert_eq!(writer.into_inner().len(), 8);
+        assert_eq!(
+            CobsFrameWriter::new(&mut output[..], &mut [], Method::Auto).write_frame(&[0x11]),
+            Err(FrameError::Encode(EncodeError::OutputTooSmall {
+                required: 3
+            }))
+        );
+    }
+
+    #[test]
+    fn reader_errors() {
+        let stream = [
+            0x00, 0x05, 0x11, 0x00, // Truncated group
+            0x0C, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB,
+            0x00, // Too long for the buffer
+            0x02, 0x66, 0x00, 0x02, 0x77, // Unterminated
+        ];
+        let inner = ChunkedReader {
+            data: &stream,
+            chunk_size: 3,
+        };
+        let mut buf = [0; 5];
+        let mut reader = CobsFrameReader::new(inner, &mut buf);
+        assert!(matches!(
+            reader.read_frame(),
+            Err(FrameError::Decode(DecodeError::TruncatedGroup { .. }))
+        ));
+        assert_eq!(
+            reader.read_frame(),
+            Err(FrameError::Decode(DecodeError::OutputTooSmall))
+        );
+        assert_eq!(reader.read_frame(), Ok(Some(&[0x66][..])));
+        assert_eq!(reader.read_frame(), Err(FrameError::UnexpectedEof));
+        assert_eq!(reader.read_frame(), Ok(None));
+    }
+}
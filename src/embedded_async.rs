@@ -0,0 +1,131 @@
+use embedded_io_async::{Read, Write};
+
+use crate::{
+    embedded::{encode_frame, FrameBuffer, FrameError},
+    Method,
+};
+
+/// COBS frame writer for [embedded_io_async::Write], requires the `embedded-io-async` feature
+///
+/// Same as [CobsFrameWriter](crate::CobsFrameWriter), for asynchronous writers.
+pub struct AsyncCobsFrameWriter<'b, W: Write> {
+    inner: W,
+    buf: &'b mut [u8],
+    method: Method,
+}
+
+impl<'b, W: Write> AsyncCobsFrameWriter<'b, W> {
+    pub fn new(inner: W, buf: &'b mut [u8], method: Method) -> AsyncCobsFrameWriter<'b, W> {
+        AsyncCobsFrameWriter { inner, buf, method }
+    }
+
+    /// Encodes `frame` and writes it followed by the delimiter.
+    ///
+    /// Nothing is written if the encoded frame does not fit into the buffer.
+    pub async fn write_frame(&mut self, frame: &[u8]) -> Result<(), FrameError<W::Error>> {
+        let encoded = encode_frame(frame, self.buf, self.method.clone())?;
+        self.inner.write_all(encoded).await.map_err(FrameError::Io)
+    }
+
+    pub async fn flush(&mut self) -> Result<(), FrameError<W::Error>> {
+        self.inner.flush().await.map_err(FrameError::Io)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// COBS frame reader for [embedded_io_async::Read], requires the `embedded-io-async` feature
+///
+/// Same as [CobsFrameReader](crate::CobsFrameReader), for asynchronous readers.
+pub struct AsyncCobsFrameReader<'b, R: Read> {
+    inner: R,
+    buf: FrameBuffer<'b>,
+}
+
+impl<'b, R: Read> AsyncCobsFrameReader<'b, R> {
+    pub fn new(inner: R, buf: &'b mut [u8]) -> AsyncCobsFrameReader<'b, R> {
+        AsyncCobsFrameReader {
+            inner,
+            buf: FrameBuffer::new(buf),
+        }
+    }
+
+    /// Reads the next frame, returning the decoded data or `None` at the end of the stream.
+    pub async fn read_frame(&mut self) -> Result<Option<&[u8]>, FrameError<R::Error>> {
+        loop {
+            if let Some(len) = self.buf.decode_next()? {
+                return Ok(Some(self.buf.frame(len)));
+            }
+            let n = self
+                .inner
+                .read(self.buf.spare())
+                .await
+                .map_err(FrameError::Io)?;
+            if n == 0 {
+                return self.buf.finish();
+            }
+            self.buf.fill(n);
+        }
+    }
+
+    /// Returns the underlying reader, discarding received data that was not returned yet.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::{AsyncCobsFrameReader, AsyncCobsFrameWriter};
+    use crate::{embedded::FrameError, encoded_size_upper_bound, DecodeError, Method};
+
+    #[quickcheck]
+    fn roundtrip(frames: Vec<Vec<u8>>) -> bool {
+        let max_encoded_size = frames
+            .iter()
+            .map(|f| encoded_size_upper_bound(f.len()) + 1)
+            .max()
+            .unwrap_or(1);
+        let mut stream = vec![0; frames.len() * max_encoded_size];
+        let mut buf = vec![0; max_encoded_size];
+
+        let mut writer = AsyncCobsFrameWriter::new(&mut stream[..], &mut buf, Method::Auto);
+        for frame in &frames {
+            block_on(writer.write_frame(frame)).unwrap();
+        }
+        let unused = writer.into_inner().len();
+        stream.truncate(stream.len() - unused);
+
+        let mut reader = AsyncCobsFrameReader::new(&stream[..], &mut buf);
+        frames
+            .iter()
+            .all(|f| block_on(reader.read_frame()).unwrap() == Some(&f[..]))
+            && block_on(reader.read_frame()).unwrap().is_none()
+    }
+
+    #[test]
+    fn reader_errors() {
+        let stream = [
+            0x05, 0x11, 0x00, 0x04, 0x11, 0x22, 0x33, 0x00, 0x02, 0x22, 0x00, 0x02,
+        ];
+        let mut buf = [0; 4];
+        let mut reader = AsyncCobsFrameReader::new(&stream[..], &mut buf);
+        assert!(matches!(
+            block_on(reader.read_frame()),
+            Err(FrameError::Decode(DecodeError::TruncatedGroup { .. }))
+        ));
+        assert_eq!(
+            block_on(reader.read_frame()),
+            Err(FrameError::Decode(DecodeError::OutputTooSmall))
+        );
+        assert_eq!(block_on(reader.read_frame()), Ok(Some(&[0x22][..])));
+        assert_eq!(
+            block_on(reader.read_frame()),
+            Err(FrameError::UnexpectedEof)
+        );
+    }
+}
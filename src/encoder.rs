@@ -0,0 +1,144 @@
+use alloc::vec::Vec;
+
+use crate::{
+    block_iter::{GroupSplitter, Split},
+    DefaultBlocks,
+};
+
+/// Incremental COBS encoder
+///
+/// Encodes a frame that is pushed piecewise, without requiring the whole message up front.
+/// Completed groups are written to the output as soon as they are known,
+/// only the currently open group (at most 254 bytes) is buffered until its overhead byte can be back-patched.
+/// The output does not include the frame delimiter.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::CobsEncoder;
+///
+/// let mut encoder = CobsEncoder::new();
+/// let mut encoded_output = vec![];
+/// encoder.push(&[1, 3, 0], &mut encoded_output);
+/// assert_eq!(encoded_output, [3, 1, 3]);
+/// encoder.push(&[7, 0, 8], &mut encoded_output);
+/// encoder.finish(&mut encoded_output);
+/// assert_eq!(encoded_output, [3, 1, 3, 2, 7, 2, 8]);
+/// ```
+pub struct CobsEncoder {
+    group: Vec<u8>, // Overhead byte placeholder followed by the data of the open group
+    splitter: GroupSplitter<DefaultBlocks>,
+}
+
+impl CobsEncoder {
+    pub fn new() -> CobsEncoder {
+        let mut group = Vec::with_capacity(255);
+        group.push(0);
+        CobsEncoder {
+            group,
+            splitter: GroupSplitter::new(),
+        }
+    }
+
+    /// Encodes the next part of the frame, appending completed groups to `output`.
+    pub fn push(&mut self, mut data: &[u8], output: &mut Vec<u8>) {
+        // The open group may already contain data from a previous push
+        while let Some(split) = self.splitter.split(data) {
+            match split {
+                Split::Zero { len, .. } => {
+                    // Zero terminates the open group and starts the next one
+                    self.group.extend_from_slice(&data[..len]);
+                    self.close_group(output);
+                    data = &data[len + 1..];
+                }
+                Split::Full { len } => {
+                    self.group.extend_from_slice(&data[..len]);
+                    self.close_group(output);
+                    data = &data[len..];
+                }
+                Split::Open { len } => {
+                    self.group.extend_from_slice(&data[..len]);
+                    data = &data[len..];
+                }
+            }
+        }
+    }
+
+    /// Completes the frame, appending the remaining encoded data to `output`.
+    ///
+    /// The encoder can be reused for the next frame afterwards.
+    pub fn finish(&mut self, output: &mut Vec<u8>) {
+        if self.splitter.group_open() {
+            self.close_group(output);
+        }
+        self.splitter.reset();
+    }
+
+    fn close_group(&mut self, output: &mut Vec<u8>) {
+        self.group[0] = self.group.len() as u8;
+        output.extend_from_slice(&self.group);
+        self.group.truncate(1);
+    }
+}
+
+impl Default for CobsEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CobsEncoder;
+    use crate::cobs_encode_to_vec;
+
+    #[quickcheck]
+    fn chunked_input(input_data: Vec<u8>, chunk_size: u8) -> bool {
+        let mut encoder = CobsEncoder::new();
+        let mut encoded = vec![];
+        for chunk in input_data.chunks(chunk_size as usize + 1) {
+            encoder.push(chunk, &mut encoded);
+        }
+        encoder.finish(&mut encoded);
+        encoded == cobs_encode_to_vec(&input_data)
+    }
+
+    #[quickcheck]
+    fn multiple_frames(frames: Vec<Vec<u8>>) -> bool {
+        let mut encoder = CobsEncoder::new();
+        frames.iter().all(|frame| {
+            let mut encoded = vec![];
+            encoder.push(frame, &mut encoded);
+            encoder.finish(&mut encoded);
+            encoded == cobs_encode_to_vec(frame)
+        })
+    }
+
+    #[test]
+    fn complete_groups_are_emitted_immediately() {
+        let mut encoder = CobsEncoder::new();
+        let mut encoded = vec![];
+        encoder.push(&[0x11; 300], &mut encoded);
+        assert_eq!(encoded.len(), 255);
+        assert_eq!(encoded[0], 0xFF);
+    }
+
+    #[test]
+    fn full_group_at_end() {
+        let input: Vec<_> = (0x01..=0xFE_u8).collect();
+        let mut encoder = CobsEncoder::new();
+        let mut encoded = vec![];
+        encoder.push(&input[..100], &mut encoded);
+        encoder.push(&input[100..], &mut encoded);
+        encoder.finish(&mut encoded);
+        assert_eq!(encoded, cobs_encode_to_vec(&input));
+    }
+
+    #[test]
+    fn empty_frame() {
+        let mut encoder = CobsEncoder::new();
+        let mut encoded = vec![];
+        encoder.finish(&mut encoded);
+        assert_eq!(encoded, [0x01]);
+    }
+}
@@ -0,0 +1,60 @@
+use core::fmt;
+
+/// Reasons why a COBS frame could not be decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// A zero byte was found inside the frame. Only a single trailing delimiter is allowed.
+    ZeroInInput { offset: usize },
+    /// The overhead byte at `offset` announces `expected` data bytes, but only `available` follow.
+    TruncatedGroup {
+        offset: usize,
+        expected: usize,
+        available: usize,
+    },
+    /// The decoded data does not fit into the output buffer.
+    OutputTooSmall,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::ZeroInInput { offset } => {
+                write!(f, "unexpected zero byte at offset {offset}")
+            }
+            DecodeError::TruncatedGroup {
+                offset,
+                expected,
+                available,
+            } => write!(
+                f,
+                "group at offset {offset} expects {expected} bytes, but only {available} are available"
+            ),
+            DecodeError::OutputTooSmall => write!(f, "output buffer too small"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Reasons why data could not be encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// The encoded data does not fit into the output buffer, which needs to hold at least `required` bytes.
+    OutputTooSmall { required: usize },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::OutputTooSmall { required } => {
+                write!(f, "output buffer too small, {required} bytes required")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {}
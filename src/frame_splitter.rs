@@ -0,0 +1,143 @@
+use crate::{block_iter::NextZeroIndex, DefaultBlocks};
+
+/// Next part of a stream found by [FrameSplitter]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FrameSplit {
+    /// All of the data belongs to the current frame, which needs more data
+    Incomplete,
+    /// `data[..end]` completes the current frame, `data[end]` is its delimiter
+    Frame { end: usize },
+    /// The first `n` bytes of the data are dropped, the delimiter of an empty frame or the rest of an overlong frame
+    Skipped(usize),
+    /// The current frame exceeds the maximum length, its data and the first `n` bytes of the data are dropped
+    TooLong(usize),
+}
+
+/// Splits a stream of encoded data at the frame delimiters, shared by the stream adapters
+///
+/// The data of the current frame is kept by the caller, either in its own buffer or by appending it elsewhere,
+/// and only new data is passed to [FrameSplitter::split], so every byte is searched for a delimiter once.
+/// The splitter tracks how much data the current frame holds, rejects frames longer than the maximum frame length
+/// and discards their data up to the next delimiter. Frame lengths refer to the encoded data without delimiter.
+#[derive(Clone)]
+pub(crate) struct FrameSplitter {
+    max_frame_length: usize,
+    pending: usize,   // Encoded bytes of the current frame kept by the caller
+    discarding: bool, // Dropping the rest of a frame that exceeded the maximum length
+}
+
+impl FrameSplitter {
+    pub(crate) fn new(max_frame_length: usize) -> FrameSplitter {
+        FrameSplitter {
+            max_frame_length,
+            pending: 0,
+            discarding: false,
+        }
+    }
+
+    #[allow(unused)] // Only needed by adapters with a configurable maximum
+    pub(crate) fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Number of bytes of the current frame kept by the caller
+    #[allow(unused)] // Only needed by adapters keeping the frame in front of the new data
+    pub(crate) fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Searches the new `data` for the end of the current frame.
+    pub(crate) fn split(&mut self, data: &[u8]) -> FrameSplit {
+        if data.is_empty() {
+            return FrameSplit::Incomplete;
+        }
+
+        let Some(i) = DefaultBlocks::next_zero_index(data) else {
+            if self.discarding {
+                return FrameSplit::Skipped(data.len());
+            }
+            if self.pending + data.len() > self.max_frame_length {
+                // Rejected before the delimiter arrives, which limits the amount of buffered data
+                self.pending = 0;
+                self.discarding = true;
+                return FrameSplit::TooLong(data.len());
+            }
+            self.pending += data.len();
+            return FrameSplit::Incomplete;
+        };
+
+        let frame_length = self.pending + i;
+        self.pending = 0;
+        if self.discarding {
+            // End of an overlong frame
+            self.discarding = false;
+            FrameSplit::Skipped(i + 1)
+        } else if frame_length > self.max_frame_length {
+            FrameSplit::TooLong(i + 1)
+        } else if frame_length == 0 {
+            // Empty frame
+            FrameSplit::Skipped(1)
+        } else {
+            FrameSplit::Frame { end: i }
+        }
+    }
+
+    /// Handles the end of the stream, returning whether it ended within a frame.
+    ///
+    /// The splitter starts over with the next frame afterwards.
+    pub(crate) fn finish(&mut self) -> bool {
+        let within_frame = self.pending > 0 || self.discarding;
+        self.pending = 0;
+        self.discarding = false;
+        within_frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrameSplit, FrameSplitter};
+
+    #[test]
+    fn frames_across_chunks() {
+        let mut splitter = FrameSplitter::new(usize::MAX);
+        assert_eq!(splitter.split(&[]), FrameSplit::Incomplete);
+        assert_eq!(splitter.split(&[0x00, 0x03]), FrameSplit::Skipped(1));
+        assert_eq!(splitter.split(&[0x03]), FrameSplit::Incomplete);
+        assert_eq!(splitter.pending(), 1);
+        assert_eq!(splitter.split(&[0x11, 0x22]), FrameSplit::Incomplete);
+        assert_eq!(splitter.split(&[0x00, 0x02]), FrameSplit::Frame { end: 0 });
+        assert_eq!(splitter.pending(), 0);
+        assert_eq!(splitter.split(&[0x02]), FrameSplit::Incomplete);
+        assert!(splitter.finish());
+        assert!(!splitter.finish());
+    }
+
+    #[test]
+    fn max_frame_length() {
+        let mut splitter = FrameSplitter::new(3);
+        assert_eq!(
+            splitter.split(&[0x03, 0x11, 0x22, 0x00]),
+            FrameSplit::Frame { end: 3 }
+        );
+        assert_eq!(
+            splitter.split(&[0x04, 0x11, 0x22, 0x33, 0x00]),
+            FrameSplit::TooLong(5)
+        );
+
+        // Overlong frame is rejected before its delimiter arrives, and discarded up to it
+        assert_eq!(splitter.split(&[0x06, 0x11]), FrameSplit::Incomplete);
+        assert_eq!(splitter.split(&[0x22, 0x33]), FrameSplit::TooLong(2));
+        assert_eq!(splitter.split(&[0x44, 0x55]), FrameSplit::Skipped(2));
+        assert_eq!(splitter.split(&[0x00, 0x02]), FrameSplit::Skipped(1));
+        assert_eq!(splitter.split(&[0x02]), FrameSplit::Incomplete);
+        assert_eq!(splitter.split(&[0x66, 0x00]), FrameSplit::Frame { end: 1 });
+    }
+
+    #[test]
+    fn stream_ends_while_discarding() {
+        let mut splitter = FrameSplitter::new(1);
+        assert_eq!(splitter.split(&[0x11, 0x22]), FrameSplit::TooLong(2));
+        assert!(splitter.finish());
+        assert_eq!(splitter.split(&[0x02, 0x00]), FrameSplit::Frame { end: 1 });
+    }
+}
@@ -1,5 +1,8 @@
-#![feature(portable_simd)]
-#![feature(array_chunks)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(feature = "nightly", feature(portable_simd))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 #[cfg(test)]
 extern crate quickcheck;
@@ -8,25 +11,102 @@ extern crate quickcheck;
 #[macro_use(quickcheck)]
 extern crate quickcheck_macros;
 
-use std::iter::once;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::{cmp::max, iter::once};
 
-use block_iter::NextZeroIndex;
-use next_zero_simd_128::SimdBlocks16;
-use next_zero_std_simd::SimdBlocksGeneric;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub use next_zero_avx::{SimdBlocksAvx2, SimdBlocksAvx512};
+#[cfg(target_arch = "aarch64")]
+pub use next_zero_neon::SimdBlocksNeon;
+pub use next_zero_simd_128::SimdBlocks16;
+#[cfg(feature = "nightly")]
+pub use next_zero_std_simd::SimdBlocksGeneric;
+pub use next_zero_swar::SimdBlocksSwar;
 
 use strum_macros::{Display, EnumIter};
 
-use crate::block_iter::BlockIter;
+pub use crate::block_iter::{BlockIter, IterPosition, NextZeroIndex, PortableBlocks};
+use crate::block_iter::{GroupSplitter, Split};
+#[cfg(feature = "alloc")]
+pub use crate::cobs_zpe::try_cobs_zpe_decode;
+pub use crate::cobs_zpe::{
+    cobs_zpe_encode_to, cobs_zpe_encoded_size_upper_bound, try_cobs_zpe_decode_to,
+};
+#[cfg(feature = "alloc")]
+pub use crate::cobsr::try_cobsr_decode;
+pub use crate::cobsr::{cobsr_encode_to, try_cobsr_decode_to};
+#[cfg(any(feature = "tokio", feature = "asynchronous-codec"))]
+pub use crate::codec::CobsCodec;
+pub use crate::cpu_features::{backend_supported, detected_backend, Backend};
+#[cfg(feature = "alloc")]
+pub use crate::decoder::{CobsDecoder, Frames};
+#[cfg(feature = "embedded-io")]
+pub use crate::embedded::{CobsFrameReader, CobsFrameWriter, FrameError};
+#[cfg(feature = "embedded-io-async")]
+pub use crate::embedded_async::{AsyncCobsFrameReader, AsyncCobsFrameWriter};
+#[cfg(feature = "alloc")]
+pub use crate::encoder::CobsEncoder;
+pub use crate::error::{DecodeError, EncodeError};
+#[cfg(feature = "alloc")]
+pub use crate::rcobs::try_rcobs_decode;
+pub use crate::rcobs::{
+    rcobs_encode_to, rcobs_encoded_size_upper_bound, try_rcobs_decode_to, RcobsEncoder,
+};
+#[cfg(feature = "std")]
+pub use crate::reader::CobsReader;
+#[cfg(feature = "std")]
+pub use crate::writer::CobsWriter;
 mod aligned_iter;
 mod block_iter;
+mod cobs_zpe;
+mod cobsr;
+#[cfg(any(feature = "tokio", feature = "asynchronous-codec"))]
+mod codec;
+mod cpu_features;
+#[cfg(feature = "alloc")]
+mod decoder;
+#[cfg(feature = "embedded-io")]
+mod embedded;
+#[cfg(feature = "embedded-io-async")]
+mod embedded_async;
+#[cfg(feature = "alloc")]
+mod encoder;
+mod error;
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+mod frame_splitter;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod next_zero_avx;
+#[cfg(target_arch = "aarch64")]
+mod next_zero_neon;
 mod next_zero_simd_128;
+#[cfg(feature = "nightly")]
 mod next_zero_std_simd;
+mod next_zero_swar;
+mod rcobs;
+#[cfg(feature = "std")]
+mod reader;
+#[cfg(feature = "std")]
+mod writer;
+
+/// Zero search used internally where no [Method] is selected
+#[cfg(feature = "nightly")]
+type DefaultBlocks = SimdBlocksGeneric<32>;
+#[cfg(not(feature = "nightly"))]
+type DefaultBlocks = SimdBlocks16;
 
 /// Determines the upper bound of the encoded message size depending on the input length
 ///
-/// COBS induces a maximum of ⌈n/254⌉ bytes overhead for n data bytes.
+/// COBS induces a maximum of ⌈n/254⌉ bytes overhead for n data bytes, but at least one byte for the first group.
 pub fn encoded_size_upper_bound(input_size: usize) -> usize {
-    input_size + (input_size + 254 - 1) / 254
+    input_size + max(input_size.div_ceil(254), 1)
+}
+
+/// Determines the upper bound of the decoded message size depending on the encoded length
+///
+/// Every frame contains at least one overhead byte, so decoding never produces more than n-1 bytes.
+pub fn decoded_size_upper_bound(input_size: usize) -> usize {
+    input_size.saturating_sub(1)
 }
 
 /// Encoding method
@@ -39,21 +119,62 @@ pub enum Method {
     Trivial,
     /// Direct translation of unhinged C implementation from wikipedia
     Crazy,
+    /// Finds zeros one `usize` word at a time using bit manipulation, for targets without SIMD instructions.
+    Swar,
     /// Optimized version which uses an iterator producing blocks that internally uses SIMD intrinsics for finding zeros in the data.
+    /// Uses std::simd instead on targets other than x86 and x86_64.
     Simd16,
-    /// Versions that use std::Simd operations to be generic over vector length
+    /// Versions that use std::Simd operations to be generic over vector length, requires the `nightly` feature
+    #[cfg(feature = "nightly")]
     StdSimd8,
+    #[cfg(feature = "nightly")]
     StdSimd16,
+    #[cfg(feature = "nightly")]
     StdSimd32,
-    /// Versions that use std::Simd operations to be generic over vector length and separate the zero-finding and splitting of large blocks, which may yield a small performance benefit
+    /// Versions that use std::Simd operations to be generic over vector length and separate the zero-finding and splitting of large blocks, which may yield a small performance benefit.
+    /// Require the `nightly` feature.
+    #[cfg(feature = "nightly")]
     StdSimd8TwoStage,
+    #[cfg(feature = "nightly")]
     StdSimd16TwoStage,
+    #[cfg(feature = "nightly")]
     StdSimd32TwoStage,
+    /// Versions that use AVX2 or AVX-512BW intrinsics for finding zeros, separating zero-finding and splitting like the TwoStage versions.
+    /// Fall back to [Backend::Portable] on CPUs without support for these instructions.
+    Avx2,
+    /// Falls back to AVX2 first on CPUs with AVX2 but without AVX-512BW.
+    Avx512,
+    /// Version that uses NEON intrinsics for finding zeros, separating zero-finding and splitting like the TwoStage versions.
+    /// Falls back to [Backend::Portable] on targets other than aarch64.
+    Neon,
+    /// Selects the fastest backend supported by the CPU at runtime, see [detected_backend]
+    Auto,
+}
+
+/// Decoding method
+///
+/// These are different methods for COBS decoding.
+/// They all produce the same output, but differ in how the end of the frame is located.
+#[derive(Clone, Display, EnumIter)]
+pub enum DecodeMethod {
+    /// Simple loop, sequentially processing every byte without (explicitly) using SIMD instructions.
+    Trivial,
+    /// Finds the frame delimiter using SIMD intrinsics, then copies whole groups.
+    /// Uses std::simd instead on targets other than x86 and x86_64.
+    Simd16,
+    /// Versions that use std::Simd operations to be generic over vector length, requires the `nightly` feature
+    #[cfg(feature = "nightly")]
+    StdSimd8,
+    #[cfg(feature = "nightly")]
+    StdSimd16,
+    #[cfg(feature = "nightly")]
+    StdSimd32,
 }
 
 /// COBS-encode data to a buffer.
 ///
 /// User must ensure that the buffer is big enough. Actual buffer usage depends on input, but always fits within encoded_size_upper_bound(input.len()).
+/// See [try_cobs_encode_to] for a variant that checks the buffer size.
 ///
 /// # Example
 ///
@@ -62,7 +183,7 @@ pub enum Method {
 ///
 /// let input_data = [1, 3, 0, 7, 0, 8];
 /// let mut encoded_output = vec![0; encoded_size_upper_bound(input_data.len())];
-/// let output_length = cobs_encode_to(&input_data, &mut encoded_output, Method::StdSimd32TwoStage);
+/// let output_length = cobs_encode_to(&input_data, &mut encoded_output, Method::Auto);
 /// encoded_output.truncate(output_length);
 /// ```
 ///
@@ -71,21 +192,277 @@ pub fn cobs_encode_to(input: &[u8], output: &mut [u8], method: Method) -> usize
         Method::Trivial => cobs_encode_to_trivial(input, output),
         Method::Simd16 => cobs_encode_to_opt(input, output),
         Method::Crazy => cobs_encode_to_c(input, output),
+        Method::Swar => cobs_encode_to_chained_iter::<SimdBlocksSwar>(input, output),
+        #[cfg(feature = "nightly")]
         Method::StdSimd8 => cobs_encode_to_std::<8>(input, output),
+        #[cfg(feature = "nightly")]
         Method::StdSimd16 => cobs_encode_to_std::<16>(input, output),
+        #[cfg(feature = "nightly")]
         Method::StdSimd32 => cobs_encode_to_std::<32>(input, output),
+        #[cfg(feature = "nightly")]
         Method::StdSimd8TwoStage => {
             cobs_encode_to_chained_iter::<SimdBlocksGeneric<8>>(input, output)
         }
+        #[cfg(feature = "nightly")]
         Method::StdSimd16TwoStage => {
             cobs_encode_to_chained_iter::<SimdBlocksGeneric<16>>(input, output)
         }
+        #[cfg(feature = "nightly")]
         Method::StdSimd32TwoStage => {
             cobs_encode_to_chained_iter::<SimdBlocksGeneric<32>>(input, output)
         }
+        Method::Avx2 => cobs_encode_to_backend_or_fallback(input, output, Backend::Avx2),
+        Method::Avx512 => cobs_encode_to_backend_or_fallback(input, output, Backend::Avx512),
+        Method::Neon => cobs_encode_to_backend_or_fallback(input, output, Backend::Neon),
+        Method::Auto => cobs_encode_to_backend(input, output, detected_backend()),
+    }
+}
+
+/// COBS-encode data to a buffer, using a custom zero search.
+///
+/// Same as [cobs_encode_to], but generic over the [NextZeroIndex] implementation instead of selecting a [Method].
+/// User must ensure that the buffer is big enough, the output always fits within encoded_size_upper_bound(input.len()).
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{cobs_encode_to_with, encoded_size_upper_bound, NextZeroIndex};
+///
+/// struct MyZeroSearch {}
+///
+/// impl NextZeroIndex for MyZeroSearch {
+///     fn next_byte_index(data: &[u8], byte: u8) -> Option<usize> {
+///         data.iter().position(|x| *x == byte)
+///     }
+/// }
+///
+/// let input_data = [1, 3, 0, 7, 0, 8];
+/// let mut encoded_output = vec![0; encoded_size_upper_bound(input_data.len())];
+/// let output_length = cobs_encode_to_with::<MyZeroSearch>(&input_data, &mut encoded_output);
+/// assert_eq!(encoded_output[..output_length], [3, 1, 3, 2, 7, 2, 8]);
+/// ```
+pub fn cobs_encode_to_with<Z: NextZeroIndex>(input: &[u8], output: &mut [u8]) -> usize {
+    cobs_encode_to_chained_iter::<Z>(input, output)
+}
+
+fn cobs_encode_to_backend(input: &[u8], output: &mut [u8], backend: Backend) -> usize {
+    assert!(backend_supported(backend));
+    match backend {
+        Backend::Portable => cobs_encode_to_chained_iter::<PortableBlocks>(input, output),
+        Backend::Sse42 => cobs_encode_to_chained_iter::<SimdBlocks16>(input, output),
+        // SAFETY: CPU support for the target features was checked
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Avx2 => unsafe { cobs_encode_to_avx2(input, output) },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Avx512 => unsafe { cobs_encode_to_avx512(input, output) },
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        Backend::Avx2 | Backend::Avx512 => unreachable!(),
+        #[cfg(target_arch = "aarch64")]
+        Backend::Neon => cobs_encode_to_chained_iter::<SimdBlocksNeon>(input, output),
+        #[cfg(not(target_arch = "aarch64"))]
+        Backend::Neon => unreachable!(),
+    }
+}
+
+/// Uses `backend` if supported, otherwise AVX2 in place of AVX-512 or the portable zero search
+fn cobs_encode_to_backend_or_fallback(input: &[u8], output: &mut [u8], backend: Backend) -> usize {
+    let backend = match backend {
+        _ if backend_supported(backend) => backend,
+        Backend::Avx512 if backend_supported(Backend::Avx2) => Backend::Avx2,
+        _ => Backend::Portable,
+    };
+    cobs_encode_to_backend(input, output, backend)
+}
+
+/// The whole encoder is compiled for AVX2, so the zero search can be inlined
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn cobs_encode_to_avx2(input: &[u8], output: &mut [u8]) -> usize {
+    cobs_encode_to_chained_iter::<SimdBlocksAvx2>(input, output)
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx512bw")]
+unsafe fn cobs_encode_to_avx512(input: &[u8], output: &mut [u8]) -> usize {
+    cobs_encode_to_chained_iter::<SimdBlocksAvx512>(input, output)
+}
+
+/// COBS-encode data to a buffer, for links using a frame delimiter other than zero.
+///
+/// The output is encoded as with [cobs_encode_to] and XORed with `sentinel` afterwards,
+/// so it contains no `sentinel` bytes. Decode with [try_cobs_decode_to_with_sentinel].
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{cobs_encode_to_with_sentinel, encoded_size_upper_bound, Method};
+///
+/// let input_data = [1, 0x7E, 0, 7];
+/// let mut encoded_output = vec![0; encoded_size_upper_bound(input_data.len())];
+/// let output_length = cobs_encode_to_with_sentinel(&input_data, &mut encoded_output, Method::Auto, 0x7E);
+/// assert_eq!(encoded_output[..output_length], [0x7D, 0x7F, 0x00, 0x7C, 0x79]);
+/// ```
+pub fn cobs_encode_to_with_sentinel(
+    input: &[u8],
+    output: &mut [u8],
+    method: Method,
+    sentinel: u8,
+) -> usize {
+    let len = cobs_encode_to(input, output, method);
+    for b in &mut output[..len] {
+        *b ^= sentinel;
     }
+    len
 }
 
+/// COBS-encode data to a buffer, checking that the encoded data fits.
+///
+/// If the buffer is smaller than encoded_size_upper_bound(input.len()), the exact encoded size is determined first,
+/// and [EncodeError::OutputTooSmall] reports it if the buffer is too small.
+/// Since [Method::Crazy] relies on the upper bound for memory safety, it falls back to [Method::Trivial] for such buffers.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{try_cobs_encode_to, EncodeError, Method};
+///
+/// let input_data = [1, 3, 0, 7, 0, 8];
+/// let mut encoded_output = [0; 7];
+/// assert_eq!(try_cobs_encode_to(&input_data, &mut encoded_output, Method::Simd16), Ok(7));
+/// assert_eq!(
+///     try_cobs_encode_to(&input_data, &mut encoded_output[..5], Method::Simd16),
+///     Err(EncodeError::OutputTooSmall { required: 7 })
+/// );
+/// ```
+///
+pub fn try_cobs_encode_to(
+    input: &[u8],
+    output: &mut [u8],
+    method: Method,
+) -> Result<usize, EncodeError> {
+    if output.len() >= encoded_size_upper_bound(input.len()) {
+        return Ok(cobs_encode_to(input, output, method));
+    }
+
+    let required = encoded_len(input);
+    if output.len() < required {
+        return Err(EncodeError::OutputTooSmall { required });
+    }
+
+    match method {
+        Method::Crazy => Ok(cobs_encode_to_trivial(input, output)),
+        _ => Ok(cobs_encode_to(input, output, method)),
+    }
+}
+
+/// COBS-encode data within its own buffer.
+///
+/// The first `len` bytes of `buf` are the payload, the rest of the buffer is headroom for the overhead bytes.
+/// encoded_size_upper_bound(len) bytes always suffice, otherwise [EncodeError::OutputTooSmall] reports the exact size required.
+/// The payload is first moved to the back of the encoded frame, then the groups are located with the SIMD zero search
+/// and moved to the front, so no second buffer is needed.
+/// This takes three passes over the data, as the exact encoded size is determined with [encoded_len] first.
+/// Where a second buffer is available, [cobs_encode_to] only reads the input once.
+///
+/// Returns the length of the encoded frame at the start of `buf`. Panics if `len` exceeds the buffer length.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::cobs_encode_in_place;
+///
+/// let mut buf = [1, 3, 0, 7, 0, 8, 0];
+/// assert_eq!(cobs_encode_in_place(&mut buf, 6), Ok(7));
+/// assert_eq!(buf, [3, 1, 3, 2, 7, 2, 8]);
+/// ```
+pub fn cobs_encode_in_place(buf: &mut [u8], len: usize) -> Result<usize, EncodeError> {
+    let required = encoded_len(&buf[..len]);
+    if buf.len() < required {
+        return Err(EncodeError::OutputTooSmall { required });
+    }
+
+    // Every group grows by at most its overhead byte, so the output never overtakes unread input
+    let mut in_idx = required - len;
+    buf.copy_within(..len, in_idx);
+
+    let mut out_idx = 0;
+    let mut splitter = GroupSplitter::<DefaultBlocks>::new();
+    while let Some(split) = splitter.split(&buf[in_idx..required]) {
+        let (block_len, code, consumed) = match split {
+            Split::Zero { len, .. } => (len, len + 1, len + 1),
+            Split::Full { len } => (len, 255, len),
+            Split::Open { len } => (len, len + 1, len),
+        };
+        buf.copy_within(in_idx..in_idx + block_len, out_idx + 1);
+        buf[out_idx] = code as u8;
+        out_idx += block_len + 1;
+        in_idx += consumed;
+    }
+
+    if splitter.group_open() && splitter.run() == 0 {
+        // Empty group after a trailing zero or for empty input, like the appended zero in BlockIter
+        buf[out_idx] = 1;
+        out_idx += 1;
+    }
+
+    debug_assert_eq!(out_idx, required);
+    Ok(out_idx)
+}
+
+/// Determines the exact encoded message size, without encoding
+///
+/// This uses the same SIMD zero search as the encoders, in contrast to [encoded_size_upper_bound] it has to look at the data.
+pub fn encoded_len(input: &[u8]) -> usize {
+    BlockIter::<DefaultBlocks>::new(input, 254)
+        .map(|block| block.len() + 1)
+        .sum()
+}
+
+/// Determines the exact decoded message size, without decoding
+///
+/// The input is validated the same way as in [try_cobs_decode_to], so this fails exactly if decoding would fail
+/// (apart from [DecodeError::OutputTooSmall]).
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{decoded_len, DecodeError};
+///
+/// assert_eq!(decoded_len(&[3, 1, 3, 2, 7, 0]), Ok(4));
+/// assert_eq!(
+///     decoded_len(&[3, 1, 3, 5, 7]),
+///     Err(DecodeError::TruncatedGroup { offset: 3, expected: 4, available: 1 })
+/// );
+/// ```
+pub fn decoded_len(input: &[u8]) -> Result<usize, DecodeError> {
+    let input = strip_delimiter::<DefaultBlocks>(input, 0)?;
+
+    let mut in_idx = 0;
+    let mut len = 0;
+    while in_idx < input.len() {
+        let code = input[in_idx] as usize;
+        let available = input.len() - in_idx - 1;
+        if code - 1 > available {
+            return Err(DecodeError::TruncatedGroup {
+                offset: in_idx,
+                expected: code - 1,
+                available,
+            });
+        }
+
+        len += code - 1;
+        in_idx += code;
+
+        if code != 255 && in_idx < input.len() {
+            // Group was terminated by a zero in the original data
+            len += 1;
+        }
+    }
+
+    Ok(len)
+}
+
+#[cfg(feature = "nightly")]
 fn cobs_encode_to_std<const N: usize>(input: &[u8], output: &mut [u8]) -> usize {
     let mut out_idx = 0;
     for block in BlockIter::<SimdBlocksGeneric<32>>::new(input, 254) {
@@ -102,7 +479,7 @@ fn cobs_encode_to_trivial(input: &[u8], output: &mut [u8]) -> usize {
     let mut written = 0;
     let mut current_block_length: u8 = 0;
 
-    for b in input.iter().cloned().chain(once(0)) {
+    for (i, b) in input.iter().cloned().chain(once(0)).enumerate() {
         if current_block_length == 0 {
             written += 1; // overhead for next group
         }
@@ -124,6 +501,11 @@ fn cobs_encode_to_trivial(input: &[u8], output: &mut [u8]) -> usize {
             let overhead_byte_index = written - 1 - current_block_length as usize;
             output[overhead_byte_index] = 255;
             current_block_length = 0;
+
+            if i == input.len() - 1 {
+                // Frame ends with a complete group, no trailing group required
+                break;
+            }
         }
     }
 
@@ -132,14 +514,12 @@ fn cobs_encode_to_trivial(input: &[u8], output: &mut [u8]) -> usize {
 
 fn cobs_encode_to_c(input: &[u8], output: &mut [u8]) -> usize {
     assert!(output.len() >= encoded_size_upper_bound(input.len()));
-    assert!(!input.is_empty());
-    assert!(!output.is_empty());
     let mut encode = &mut output[0] as *mut u8; // Encoded byte pointer
     let mut codep = encode; // Output code pointer
     encode = unsafe { encode.add(1) };
     let mut code = 1; // Code value
 
-    let mut byte = &input[0] as *const u8;
+    let mut byte = input.as_ptr();
     let mut length = input.len();
 
     while length > 0 {
@@ -169,7 +549,10 @@ fn cobs_encode_to_c(input: &[u8], output: &mut [u8]) -> usize {
         byte = unsafe { byte.add(1) };
     }
 
-    unsafe { *codep = code };
+    if codep != encode {
+        // Write final code value, unless the frame ended with a complete block
+        unsafe { *codep = code };
+    }
 
     unsafe { encode.offset_from(&output[0] as *const u8) as usize }
 }
@@ -190,9 +573,16 @@ fn cobs_encode_to_chained_iter<ZeroMethod: NextZeroIndex>(
     input: &[u8],
     output: &mut [u8],
 ) -> usize {
+    if input.is_empty() {
+        // Single empty group, BlockIter needs a non-zero block size
+        output[0] = 1;
+        return 1;
+    }
+
     let mut out_idx = 0;
     // This finds large non-zero blocks first, and then divides them, instead of directly finding non-zero blocks with maximum size
-    for large_block in BlockIter::<ZeroMethod>::new(input, input.len()) {
+    let mut large_blocks = BlockIter::<ZeroMethod>::new(input, input.len()).peekable();
+    while let Some(large_block) = large_blocks.next() {
         // Manual flat_map, since chunking empty slice does not yield an empty slice, but we want to preserve it...
         if !large_block.is_empty() {
             for block in large_block.chunks(254) {
@@ -201,6 +591,11 @@ fn cobs_encode_to_chained_iter<ZeroMethod: NextZeroIndex>(
                 output[out_idx + 1..out_idx + 1 + block.len()].copy_from_slice(block);
                 out_idx += block.len() + 1;
             }
+            if large_block.len() % 254 == 0 && large_blocks.peek().is_some() {
+                // Complete groups don't imply a zero, so the zero after this block needs an empty group
+                output[out_idx] = 1;
+                out_idx += 1;
+            }
         } else {
             output[out_idx] = large_block.len() as u8 + 1;
             // Copy all
@@ -212,13 +607,311 @@ fn cobs_encode_to_chained_iter<ZeroMethod: NextZeroIndex>(
     out_idx
 }
 
+/// COBS-decode a frame to a buffer.
+///
+/// Decoding stops at the first zero byte, so the input may include the trailing frame delimiter.
+/// User must ensure that the buffer is big enough, decoded data always fits within decoded_size_upper_bound(input.len()).
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{cobs_decode_to, decoded_size_upper_bound, DecodeMethod};
+///
+/// let encoded_data = [3, 1, 3, 2, 7, 2, 8, 0];
+/// let mut decoded_output = vec![0; decoded_size_upper_bound(encoded_data.len())];
+/// let output_length = cobs_decode_to(&encoded_data, &mut decoded_output, DecodeMethod::Simd16);
+/// decoded_output.truncate(output_length);
+/// assert_eq!(decoded_output, [1, 3, 0, 7, 0, 8]);
+/// ```
+///
+/// # Panics
+///
+/// All methods panic on a truncated group and if the buffer is too small,
+/// use [try_cobs_decode_to] to handle malformed input gracefully.
+pub fn cobs_decode_to(input: &[u8], output: &mut [u8], method: DecodeMethod) -> usize {
+    match method {
+        DecodeMethod::Trivial => cobs_decode_to_trivial(input, output),
+        DecodeMethod::Simd16 => cobs_decode_to_simd::<SimdBlocks16>(input, output),
+        #[cfg(feature = "nightly")]
+        DecodeMethod::StdSimd8 => cobs_decode_to_simd::<SimdBlocksGeneric<8>>(input, output),
+        #[cfg(feature = "nightly")]
+        DecodeMethod::StdSimd16 => cobs_decode_to_simd::<SimdBlocksGeneric<16>>(input, output),
+        #[cfg(feature = "nightly")]
+        DecodeMethod::StdSimd32 => cobs_decode_to_simd::<SimdBlocksGeneric<32>>(input, output),
+    }
+}
+
+fn cobs_decode_to_trivial(input: &[u8], output: &mut [u8]) -> usize {
+    // Everything after the delimiter is ignored, as in cobs_decode_to_simd
+    let frame_length = input.iter().position(|b| *b == 0).unwrap_or(input.len());
+    match try_cobs_decode_to_trivial(&input[..frame_length], output, 0) {
+        Ok(written) => written,
+        Err(e) => panic!("{e}"),
+    }
+}
+
+fn cobs_decode_to_simd<ZeroMethod: NextZeroIndex>(input: &[u8], output: &mut [u8]) -> usize {
+    // Everything after the delimiter is ignored. This also guarantees that no overhead byte is zero.
+    let frame_length = ZeroMethod::next_zero_index(input).unwrap_or(input.len());
+    match cobs_decode_groups(&input[..frame_length], output, 0) {
+        Ok(written) => written,
+        Err(e) => panic!("{e}"),
+    }
+}
+
+/// COBS-decode a frame to a buffer, rejecting malformed input.
+///
+/// The input may end with a single frame delimiter, any other zero byte is reported as error.
+/// Unlike [cobs_decode_to], this never panics.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{try_cobs_decode_to, DecodeError, DecodeMethod};
+///
+/// let mut decoded_output = [0; 8];
+/// assert_eq!(try_cobs_decode_to(&[3, 1, 3, 2, 7, 0], &mut decoded_output, DecodeMethod::Simd16), Ok(4));
+/// assert_eq!(
+///     try_cobs_decode_to(&[3, 1, 0, 2, 7], &mut decoded_output, DecodeMethod::Simd16),
+///     Err(DecodeError::ZeroInInput { offset: 2 })
+/// );
+/// ```
+///
+pub fn try_cobs_decode_to(
+    input: &[u8],
+    output: &mut [u8],
+    method: DecodeMethod,
+) -> Result<usize, DecodeError> {
+    try_cobs_decode_to_with_sentinel(input, output, method, 0)
+}
+
+/// COBS-decode a frame encoded with [cobs_encode_to_with_sentinel], rejecting malformed input.
+///
+/// The input may end with a single `sentinel` byte as frame delimiter, any other `sentinel` byte is reported as
+/// [DecodeError::ZeroInInput]. The SIMD methods search for the `sentinel` byte directly.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{try_cobs_decode_to_with_sentinel, DecodeMethod};
+///
+/// let mut decoded_output = [0; 8];
+/// let encoded_data = [0x7D, 0x7F, 0x00, 0x7C, 0x79, 0x7E];
+/// assert_eq!(try_cobs_decode_to_with_sentinel(&encoded_data, &mut decoded_output, DecodeMethod::Simd16, 0x7E), Ok(4));
+/// assert_eq!(decoded_output[..4], [1, 0x7E, 0, 7]);
+/// ```
+pub fn try_cobs_decode_to_with_sentinel(
+    input: &[u8],
+    output: &mut [u8],
+    method: DecodeMethod,
+    sentinel: u8,
+) -> Result<usize, DecodeError> {
+    match method {
+        DecodeMethod::Trivial => try_cobs_decode_to_trivial(input, output, sentinel),
+        DecodeMethod::Simd16 => try_cobs_decode_to_simd::<SimdBlocks16>(input, output, sentinel),
+        #[cfg(feature = "nightly")]
+        DecodeMethod::StdSimd8 => {
+            try_cobs_decode_to_simd::<SimdBlocksGeneric<8>>(input, output, sentinel)
+        }
+        #[cfg(feature = "nightly")]
+        DecodeMethod::StdSimd16 => {
+            try_cobs_decode_to_simd::<SimdBlocksGeneric<16>>(input, output, sentinel)
+        }
+        #[cfg(feature = "nightly")]
+        DecodeMethod::StdSimd32 => {
+            try_cobs_decode_to_simd::<SimdBlocksGeneric<32>>(input, output, sentinel)
+        }
+    }
+}
+
+/// COBS-decode a frame to a newly allocated vector, rejecting malformed input.
+///
+/// See [try_cobs_decode_to] for details.
+#[cfg(feature = "alloc")]
+pub fn try_cobs_decode(input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    try_cobs_decode_with_sentinel(input, 0)
+}
+
+/// COBS-decode a frame encoded with [cobs_encode_to_with_sentinel] to a newly allocated vector, rejecting malformed input.
+///
+/// See [try_cobs_decode_to_with_sentinel] for details.
+#[cfg(feature = "alloc")]
+pub fn try_cobs_decode_with_sentinel(input: &[u8], sentinel: u8) -> Result<Vec<u8>, DecodeError> {
+    let mut res = vec![0; decoded_size_upper_bound(input.len())];
+    let len = try_cobs_decode_to_simd::<DefaultBlocks>(input, &mut res, sentinel)?;
+    res.truncate(len);
+    Ok(res)
+}
+
+/// COBS-decode a frame within its own buffer, rejecting malformed input.
+///
+/// The decoded data is moved to the start of the buffer, its length is returned.
+/// Like [try_cobs_decode_to], a single trailing delimiter is allowed.
+/// On error, the contents of the buffer are unspecified.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::cobs_decode_in_place;
+///
+/// let mut buf = [3, 1, 3, 2, 7, 2, 8, 0];
+/// let len = cobs_decode_in_place(&mut buf).unwrap();
+/// assert_eq!(&buf[..len], [1, 3, 0, 7, 0, 8]);
+/// ```
+pub fn cobs_decode_in_place(buf: &mut [u8]) -> Result<usize, DecodeError> {
+    let len = strip_delimiter::<DefaultBlocks>(buf, 0)?.len();
+
+    // Output shrinks by at least the overhead byte per group, so it never overtakes unread input
+    let mut in_idx = 0;
+    let mut out_idx = 0;
+    while in_idx < len {
+        let code = buf[in_idx] as usize;
+        let available = len - in_idx - 1;
+        if code - 1 > available {
+            return Err(DecodeError::TruncatedGroup {
+                offset: in_idx,
+                expected: code - 1,
+                available,
+            });
+        }
+
+        buf.copy_within(in_idx + 1..in_idx + code, out_idx);
+        out_idx += code - 1;
+        in_idx += code;
+
+        if code != 255 && in_idx < len {
+            // Group was terminated by a zero in the original data
+            buf[out_idx] = 0;
+            out_idx += 1;
+        }
+    }
+
+    Ok(out_idx)
+}
+
+fn try_cobs_decode_to_trivial(
+    input: &[u8],
+    output: &mut [u8],
+    sentinel: u8,
+) -> Result<usize, DecodeError> {
+    let mut written = 0;
+    let mut remaining_group_length: u8 = 0;
+    let mut previous_code: u8 = 255;
+    let mut group_offset = 0;
+
+    for (offset, b) in input.iter().map(|b| b ^ sentinel).enumerate() {
+        if b == 0 {
+            if offset == input.len() - 1 {
+                // Trailing delimiter
+                break;
+            }
+            return Err(DecodeError::ZeroInInput { offset });
+        }
+
+        if remaining_group_length == 0 {
+            // Start of next group, b is the overhead byte
+            if previous_code != 255 {
+                // Previous group ended with a zero
+                *output.get_mut(written).ok_or(DecodeError::OutputTooSmall)? = 0;
+                written += 1;
+            }
+            previous_code = b;
+            remaining_group_length = b - 1;
+            group_offset = offset;
+            continue;
+        }
+
+        *output.get_mut(written).ok_or(DecodeError::OutputTooSmall)? = b;
+        written += 1;
+        remaining_group_length -= 1;
+    }
+
+    if remaining_group_length != 0 {
+        let expected = previous_code as usize - 1;
+        return Err(DecodeError::TruncatedGroup {
+            offset: group_offset,
+            expected,
+            available: expected - remaining_group_length as usize,
+        });
+    }
+
+    Ok(written)
+}
+
+fn try_cobs_decode_to_simd<ZeroMethod: NextZeroIndex>(
+    input: &[u8],
+    output: &mut [u8],
+    sentinel: u8,
+) -> Result<usize, DecodeError> {
+    cobs_decode_groups(
+        strip_delimiter::<ZeroMethod>(input, sentinel)?,
+        output,
+        sentinel,
+    )
+}
+
+/// Removes the optional trailing delimiter, rejecting any other delimiter byte.
+fn strip_delimiter<ZeroMethod: NextZeroIndex>(
+    input: &[u8],
+    sentinel: u8,
+) -> Result<&[u8], DecodeError> {
+    match ZeroMethod::next_byte_index(input, sentinel) {
+        None => Ok(input),
+        Some(offset) if offset == input.len() - 1 => Ok(&input[..offset]),
+        Some(offset) => Err(DecodeError::ZeroInInput { offset }),
+    }
+}
+
+/// Decodes a frame which must not contain any delimiter bytes, copying whole groups at once.
+///
+/// The input is XORed with `sentinel` while decoding.
+fn cobs_decode_groups(input: &[u8], output: &mut [u8], sentinel: u8) -> Result<usize, DecodeError> {
+    let mut in_idx = 0;
+    let mut out_idx = 0;
+    while in_idx < input.len() {
+        let code = (input[in_idx] ^ sentinel) as usize;
+        let available = input.len() - in_idx - 1;
+        if code - 1 > available {
+            return Err(DecodeError::TruncatedGroup {
+                offset: in_idx,
+                expected: code - 1,
+                available,
+            });
+        }
+
+        let block = &input[in_idx + 1..in_idx + code];
+        // Copy all
+        let output_block = output
+            .get_mut(out_idx..out_idx + block.len())
+            .ok_or(DecodeError::OutputTooSmall)?;
+        output_block.copy_from_slice(block);
+        if sentinel != 0 {
+            for b in output_block {
+                *b ^= sentinel;
+            }
+        }
+        out_idx += block.len();
+        in_idx += code;
+
+        if code != 255 && in_idx < input.len() {
+            // Group was terminated by a zero in the original data
+            *output.get_mut(out_idx).ok_or(DecodeError::OutputTooSmall)? = 0;
+            out_idx += 1;
+        }
+    }
+
+    Ok(out_idx)
+}
+
+// Also used as reference encoder by the tests, which can always allocate
+#[cfg(any(feature = "alloc", test))]
 #[allow(unused)]
 pub fn cobs_encode_to_vec(input: &[u8]) -> Vec<u8> {
     let mut res = vec![];
 
     let mut current_block_length: u8 = 0;
 
-    for b in input.iter().cloned().chain(once(0)) {
+    for (i, b) in input.iter().cloned().chain(once(0)).enumerate() {
         if current_block_length == 0 {
             res.push(0) // overhead for next group
         }
@@ -239,12 +932,23 @@ pub fn cobs_encode_to_vec(input: &[u8]) -> Vec<u8> {
             let overhead_byte_index = res.len() - 1 - current_block_length as usize;
             res[overhead_byte_index] = 255;
             current_block_length = 0;
+
+            if i == input.len() - 1 {
+                // Frame ends with a complete group, no trailing group required
+                break;
+            }
         }
     }
 
     res
 }
 
+/// COBS-decode a frame to a newly allocated vector.
+///
+/// # Panics
+///
+/// Panics on malformed input, use [try_cobs_decode] to handle it gracefully.
+#[cfg(feature = "alloc")]
 #[allow(unused)]
 pub fn cobs_decode(input: &[u8]) -> Vec<u8> {
     let mut res = vec![];
@@ -267,12 +971,22 @@ pub fn cobs_decode(input: &[u8]) -> Vec<u8> {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "nightly")]
+    use crate::next_zero_std_simd::SimdBlocksGeneric;
     use crate::{
-        cobs_decode, cobs_encode_to_c, cobs_encode_to_chained_iter, cobs_encode_to_opt,
-        cobs_encode_to_trivial, cobs_encode_to_vec, encoded_size_upper_bound,
-        next_zero_simd_128::SimdBlocks16, next_zero_std_simd::SimdBlocksGeneric,
+        backend_supported, cobs_decode_in_place, cobs_decode_to, cobs_decode_to_simd,
+        cobs_decode_to_trivial, cobs_encode_in_place, cobs_encode_to, cobs_encode_to_backend,
+        cobs_encode_to_c, cobs_encode_to_chained_iter, cobs_encode_to_opt, cobs_encode_to_trivial,
+        cobs_encode_to_vec, cobs_encode_to_with_sentinel, decoded_len, decoded_size_upper_bound,
+        encoded_len, encoded_size_upper_bound, next_zero_simd_128::SimdBlocks16,
+        next_zero_swar::SimdBlocksSwar, try_cobs_decode_to_simd, try_cobs_decode_to_trivial,
+        try_cobs_decode_to_with_sentinel, try_cobs_encode_to, Backend, DecodeError, DecodeMethod,
+        EncodeError, Method,
     };
+    #[cfg(feature = "alloc")]
+    use crate::{cobs_decode, try_cobs_decode, try_cobs_decode_with_sentinel};
     use concat_idents::concat_idents;
+    use strum::IntoEnumIterator;
 
     type EncodingFunction = dyn Fn(&[u8]) -> Vec<u8>;
 
@@ -329,6 +1043,40 @@ mod tests {
                 }
             });
 
+            concat_idents!(test_name = $name, _, encoding_6_zeros_short {
+                #[test]
+                fn test_name() {
+                    assert_eq!(
+                        $func(&[0x11, 0x00, 0x00, 0x00]),
+                        vec![0x02, 0x11, 0x01, 0x01, 0x01]
+                    )
+                }
+            });
+
+            concat_idents!(test_name = $name, _, encoding_7_no_zeros_full_block {
+                #[test]
+                fn test_name() {
+                    let input: Vec<_> = (0x01..=0xFE_u8).collect();
+
+                    let mut expected_output = vec![0xFF];
+                    expected_output.extend(0x01..=0xFE);
+
+                    assert_eq!($func(&input), expected_output)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, encoding_8_leading_zero_full_block {
+                #[test]
+                fn test_name() {
+                    let input: Vec<_> = (0x00..=0xFE_u8).collect();
+
+                    let mut expected_output = vec![0x01, 0xFF];
+                    expected_output.extend(0x01..=0xFE);
+
+                    assert_eq!($func(&input), expected_output)
+                }
+            });
+
             concat_idents!(test_name = $name, _, encoding_9_no_zeros_long {
                 #[test]
                 fn test_name() {
@@ -341,6 +1089,41 @@ mod tests {
                     assert_eq!($func(&input), expected_output)
                 }
             });
+
+            concat_idents!(test_name = $name, _, encoding_10_trailing_zero_long {
+                #[test]
+                fn test_name() {
+                    let mut input: Vec<_> = (0x02..=0xFF_u8).collect();
+                    input.push(0x00);
+
+                    let mut expected_output = vec![0xFF];
+                    expected_output.extend(0x02..=0xFF);
+                    expected_output.extend([0x01, 0x01]);
+
+                    assert_eq!($func(&input), expected_output)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, encoding_11_zero_at_end_of_block {
+                #[test]
+                fn test_name() {
+                    let mut input: Vec<_> = (0x03..=0xFF_u8).collect();
+                    input.extend([0x00, 0x01]);
+
+                    let mut expected_output = vec![0xFE];
+                    expected_output.extend(0x03..=0xFF);
+                    expected_output.extend([0x02, 0x01]);
+
+                    assert_eq!($func(&input), expected_output)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, encoding_empty {
+                #[test]
+                fn test_name() {
+                    assert_eq!($func(&[]), vec![0x01])
+                }
+            });
         };
     }
 
@@ -355,6 +1138,7 @@ mod tests {
         encode_to_wrapper(cobs_encode_to_chained_iter::<SimdBlocks16>)
     );
 
+    #[cfg(feature = "nightly")]
     encode_tests!(
         chained_iter_32,
         encode_to_wrapper(cobs_encode_to_chained_iter::<SimdBlocksGeneric<32>>)
@@ -362,6 +1146,495 @@ mod tests {
 
     encode_tests!(c, encode_to_wrapper(cobs_encode_to_c));
 
+    encode_tests!(
+        swar,
+        encode_to_wrapper(cobs_encode_to_chained_iter::<SimdBlocksSwar>)
+    );
+
+    #[quickcheck]
+    fn methods_agree(input_data: Vec<u8>) -> bool {
+        let expected_output = cobs_encode_to_vec(&input_data);
+        Method::iter().all(|method| {
+            let mut output_data = vec![0; encoded_size_upper_bound(input_data.len())];
+            let s = cobs_encode_to(&input_data, &mut output_data, method);
+            output_data.truncate(s);
+            output_data == expected_output
+        })
+    }
+
+    #[test]
+    fn chained_iter_keeps_zero_after_full_block() {
+        let mut input = vec![0x01; 254];
+        input.extend([0x00, 0x05]);
+
+        let mut expected_output = vec![0xFF];
+        expected_output.extend([0x01; 254]);
+        expected_output.extend([0x01, 0x02, 0x05]);
+
+        for method in Method::iter() {
+            let mut output_data = vec![0; encoded_size_upper_bound(input.len())];
+            let s = cobs_encode_to(&input, &mut output_data, method);
+            assert_eq!(output_data[..s], expected_output);
+        }
+    }
+
+    #[quickcheck]
+    fn backends_agree(input_data: Vec<u8>) -> bool {
+        let expected_output = cobs_encode_to_vec(&input_data);
+        Backend::iter()
+            .filter(|b| backend_supported(*b))
+            .all(|backend| {
+                let mut output_data = vec![0; encoded_size_upper_bound(input_data.len())];
+                let s = cobs_encode_to_backend(&input_data, &mut output_data, backend);
+                output_data.truncate(s);
+                output_data == expected_output
+            })
+    }
+
+    #[quickcheck]
+    fn encoded_len_exact(input_data: Vec<u8>) -> bool {
+        encoded_len(&input_data) == cobs_encode_to_vec(&input_data).len()
+    }
+
+    #[quickcheck]
+    fn decoded_len_exact(input_data: Vec<u8>) -> bool {
+        decoded_len(&cobs_encode_to_vec(&input_data)) == Ok(input_data.len())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[quickcheck]
+    fn decoded_len_matches_decoding(input_data: Vec<u8>) -> bool {
+        decoded_len(&input_data) == try_cobs_decode(&input_data).map(|v| v.len())
+    }
+
+    #[quickcheck]
+    fn try_encode_exact_buffer(input_data: Vec<u8>) -> bool {
+        let expected_output = cobs_encode_to_vec(&input_data);
+        Method::iter().all(|method| {
+            let mut output_data = vec![0; expected_output.len()];
+            try_cobs_encode_to(&input_data, &mut output_data, method) == Ok(expected_output.len())
+                && output_data == expected_output
+        })
+    }
+
+    #[quickcheck]
+    fn try_encode_buffer_too_small(input_data: Vec<u8>) -> bool {
+        let required = cobs_encode_to_vec(&input_data).len();
+        Method::iter().all(|method| {
+            let mut output_data = vec![0; required - 1];
+            try_cobs_encode_to(&input_data, &mut output_data, method)
+                == Err(EncodeError::OutputTooSmall { required })
+        })
+    }
+
+    fn encode_in_place_wrapper(input: &[u8]) -> Vec<u8> {
+        let mut buf = input.to_vec();
+        buf.resize(encoded_size_upper_bound(input.len()), 0);
+        let len = cobs_encode_in_place(&mut buf, input.len()).unwrap();
+        buf.truncate(len);
+        buf
+    }
+
+    encode_tests!(in_place, encode_in_place_wrapper);
+
+    #[quickcheck]
+    fn encode_in_place(input_data: Vec<u8>) -> bool {
+        encode_in_place_wrapper(&input_data) == cobs_encode_to_vec(&input_data)
+    }
+
+    #[quickcheck]
+    fn encode_in_place_buffer_too_small(input_data: Vec<u8>) -> bool {
+        let required = cobs_encode_to_vec(&input_data).len();
+        let mut buf = input_data.clone();
+        buf.resize(required - 1, 0);
+        cobs_encode_in_place(&mut buf, input_data.len())
+            == Err(EncodeError::OutputTooSmall { required })
+    }
+
+    type DecodingFunction = dyn Fn(&[u8]) -> Vec<u8>;
+
+    fn decode_to_wrapper(function: fn(&[u8], &mut [u8]) -> usize) -> Box<DecodingFunction> {
+        Box::new(move |input: &[u8]| {
+            let mut output_data = vec![0; decoded_size_upper_bound(input.len())];
+            let s = function(input, &mut output_data);
+            output_data.truncate(s);
+            output_data
+        })
+    }
+
+    macro_rules! decode_tests {
+        ($name:ident, $func:expr) => {
+
+            concat_idents!(test_name = $name, _, decoding_1_one_zero {
+                #[test]
+                fn test_name() {
+                    assert_eq!($func(&[1, 1]), vec![0])
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_2_only_zeros_short {
+                #[test]
+                fn test_name() {
+                    assert_eq!($func(&[1, 1, 1]), vec![0, 0])
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_3 {
+                #[test]
+                fn test_name() {
+                    assert_eq!($func(&[0x01, 0x02, 0x11, 0x01]), vec![0, 0x11, 0])
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_4_zeros_short {
+                #[test]
+                fn test_name() {
+                    assert_eq!(
+                        $func(&[0x03, 0x11, 0x22, 0x02, 0x33]),
+                        vec![0x11, 0x22, 0x00, 0x33]
+                    )
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_5_no_zeros_short {
+                #[test]
+                fn test_name() {
+                    assert_eq!(
+                        $func(&[0x05, 0x11, 0x22, 0x33, 0x44]),
+                        vec![0x11, 0x22, 0x33, 0x44]
+                    )
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_6_zeros_short {
+                #[test]
+                fn test_name() {
+                    assert_eq!(
+                        $func(&[0x02, 0x11, 0x01, 0x01, 0x01]),
+                        vec![0x11, 0x00, 0x00, 0x00]
+                    )
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_7_no_zeros_full_block {
+                #[test]
+                fn test_name() {
+                    let mut input = vec![0xFF];
+                    input.extend(0x01..=0xFE);
+
+                    let expected_output: Vec<_> = (0x01..=0xFE_u8).collect();
+
+                    assert_eq!($func(&input), expected_output)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_8_leading_zero_full_block {
+                #[test]
+                fn test_name() {
+                    let mut input = vec![0x01, 0xFF];
+                    input.extend(0x01..=0xFE);
+
+                    let expected_output: Vec<_> = (0x00..=0xFE_u8).collect();
+
+                    assert_eq!($func(&input), expected_output)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_9_no_zeros_long {
+                #[test]
+                fn test_name() {
+                    let mut input = vec![0xFF];
+                    input.extend(0x01..=0xFE);
+                    input.extend([0x02, 0xFF]);
+
+                    let expected_output: Vec<_> = (0x01..=0xFF_u8).collect();
+
+                    assert_eq!($func(&input), expected_output)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_10_trailing_zero_long {
+                #[test]
+                fn test_name() {
+                    let mut input = vec![0xFF];
+                    input.extend(0x02..=0xFF);
+                    input.extend([0x01, 0x01]);
+
+                    let mut expected_output: Vec<_> = (0x02..=0xFF_u8).collect();
+                    expected_output.push(0x00);
+
+                    assert_eq!($func(&input), expected_output)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_11_zero_at_end_of_block {
+                #[test]
+                fn test_name() {
+                    let mut input = vec![0xFE];
+                    input.extend(0x03..=0xFF);
+                    input.extend([0x02, 0x01]);
+
+                    let mut expected_output: Vec<_> = (0x03..=0xFF_u8).collect();
+                    expected_output.extend([0x00, 0x01]);
+
+                    assert_eq!($func(&input), expected_output)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_stops_at_delimiter {
+                #[test]
+                fn test_name() {
+                    assert_eq!(
+                        $func(&[0x03, 0x11, 0x22, 0x02, 0x33, 0x00, 0x02, 0x44]),
+                        vec![0x11, 0x22, 0x00, 0x33]
+                    )
+                }
+            });
+
+            concat_idents!(test_name = $name, _, decoding_roundtrip {
+                #[quickcheck]
+                fn test_name(input_data: Vec<u8>) -> bool {
+                    $func(&cobs_encode_to_vec(&input_data)) == input_data
+                }
+            });
+        };
+    }
+
+    decode_tests!(decode_trivial, decode_to_wrapper(cobs_decode_to_trivial));
+
+    decode_tests!(
+        decode_simd16,
+        decode_to_wrapper(cobs_decode_to_simd::<SimdBlocks16>)
+    );
+
+    #[cfg(feature = "nightly")]
+    decode_tests!(
+        decode_std_simd_8,
+        decode_to_wrapper(cobs_decode_to_simd::<SimdBlocksGeneric<8>>)
+    );
+
+    #[cfg(feature = "nightly")]
+    decode_tests!(
+        decode_std_simd_16,
+        decode_to_wrapper(cobs_decode_to_simd::<SimdBlocksGeneric<16>>)
+    );
+
+    #[cfg(feature = "nightly")]
+    decode_tests!(
+        decode_std_simd_32,
+        decode_to_wrapper(cobs_decode_to_simd::<SimdBlocksGeneric<32>>)
+    );
+
+    #[test]
+    fn decoding_malformed_input_panics() {
+        for method in DecodeMethod::iter() {
+            for (input, output_length) in [
+                (&[0x03, 0x11, 0x22, 0x05, 0x33][..], 8),
+                (&[0x04, 0x11, 0x22, 0x00, 0x02, 0x33][..], 8),
+                (&[0x03, 0x11, 0x22, 0x02, 0x33][..], 3),
+            ] {
+                let method = method.clone();
+                let result = std::panic::catch_unwind(|| {
+                    let mut output_data = vec![0; output_length];
+                    cobs_decode_to(input, &mut output_data, method)
+                });
+                assert!(result.is_err());
+            }
+        }
+    }
+
+    type TryDecodingFunction = dyn Fn(&[u8]) -> Result<Vec<u8>, DecodeError>;
+
+    fn try_decode_to_wrapper(
+        function: fn(&[u8], &mut [u8]) -> Result<usize, DecodeError>,
+    ) -> Box<TryDecodingFunction> {
+        Box::new(move |input: &[u8]| {
+            let mut output_data = vec![0; decoded_size_upper_bound(input.len())];
+            let s = function(input, &mut output_data)?;
+            output_data.truncate(s);
+            Ok(output_data)
+        })
+    }
+
+    macro_rules! decode_error_tests {
+        ($name:ident, $func:expr) => {
+
+            concat_idents!(test_name = $name, _, valid_frame {
+                #[test]
+                fn test_name() {
+                    assert_eq!($func(&[0x03, 0x11, 0x22, 0x02, 0x33]), Ok(vec![0x11, 0x22, 0x00, 0x33]))
+                }
+            });
+
+            concat_idents!(test_name = $name, _, trailing_delimiter {
+                #[test]
+                fn test_name() {
+                    assert_eq!($func(&[0x03, 0x11, 0x22, 0x02, 0x33, 0x00]), Ok(vec![0x11, 0x22, 0x00, 0x33]))
+                }
+            });
+
+            concat_idents!(test_name = $name, _, zero_overhead_byte {
+                #[test]
+                fn test_name() {
+                    assert_eq!($func(&[0x00, 0x11]), Err(DecodeError::ZeroInInput { offset: 0 }))
+                }
+            });
+
+            concat_idents!(test_name = $name, _, zero_in_group {
+                #[test]
+                fn test_name() {
+                    assert_eq!(
+                        $func(&[0x03, 0x11, 0x00, 0x02, 0x33]),
+                        Err(DecodeError::ZeroInInput { offset: 2 })
+                    )
+                }
+            });
+
+            concat_idents!(test_name = $name, _, truncated_group {
+                #[test]
+                fn test_name() {
+                    assert_eq!(
+                        $func(&[0x03, 0x11, 0x22, 0x05, 0x33]),
+                        Err(DecodeError::TruncatedGroup { offset: 3, expected: 4, available: 1 })
+                    )
+                }
+            });
+
+            concat_idents!(test_name = $name, _, truncated_group_before_delimiter {
+                #[test]
+                fn test_name() {
+                    assert_eq!(
+                        $func(&[0x04, 0x11, 0x22, 0x00]),
+                        Err(DecodeError::TruncatedGroup { offset: 0, expected: 3, available: 2 })
+                    )
+                }
+            });
+
+            concat_idents!(test_name = $name, _, roundtrip {
+                #[quickcheck]
+                fn test_name(input_data: Vec<u8>) -> bool {
+                    $func(&cobs_encode_to_vec(&input_data)) == Ok(input_data)
+                }
+            });
+
+            concat_idents!(test_name = $name, _, arbitrary_input_does_not_panic {
+                #[quickcheck]
+                fn test_name(input_data: Vec<u8>) -> bool {
+                    let _ = $func(&input_data);
+                    true
+                }
+            });
+        };
+    }
+
+    decode_error_tests!(
+        try_decode_trivial,
+        try_decode_to_wrapper(|input, output| try_cobs_decode_to_trivial(input, output, 0))
+    );
+
+    decode_error_tests!(
+        try_decode_simd16,
+        try_decode_to_wrapper(|input, output| try_cobs_decode_to_simd::<SimdBlocks16>(
+            input, output, 0
+        ))
+    );
+
+    #[cfg(feature = "nightly")]
+    decode_error_tests!(
+        try_decode_std_simd_32,
+        try_decode_to_wrapper(
+            |input, output| try_cobs_decode_to_simd::<SimdBlocksGeneric<32>>(input, output, 0)
+        )
+    );
+
+    fn decode_in_place_wrapper(input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        let mut buf = input.to_vec();
+        let len = cobs_decode_in_place(&mut buf)?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+
+    decode_error_tests!(decode_in_place, decode_in_place_wrapper);
+
+    #[test]
+    fn decode_in_place_long_groups() {
+        let mut input: Vec<_> = (0..600).map(|i| (i % 7) as u8 + 1).collect();
+        input[300] = 0;
+        input[301] = 0;
+        let encoded = cobs_encode_to_vec(&input);
+        assert_eq!(decode_in_place_wrapper(&encoded), Ok(input));
+    }
+
+    #[test]
+    fn try_decoding_output_too_small() {
+        let mut output_data = [0; 3];
+        assert_eq!(
+            try_cobs_decode_to_trivial(&[0x03, 0x11, 0x22, 0x02, 0x33], &mut output_data, 0),
+            Err(DecodeError::OutputTooSmall)
+        );
+        assert_eq!(
+            try_cobs_decode_to_simd::<SimdBlocks16>(
+                &[0x03, 0x11, 0x22, 0x02, 0x33],
+                &mut output_data,
+                0
+            ),
+            Err(DecodeError::OutputTooSmall)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[quickcheck]
+    fn sentinel_roundtrip(input_data: Vec<u8>, sentinel: u8) -> bool {
+        let mut encoded = vec![0; encoded_size_upper_bound(input_data.len())];
+        let len = cobs_encode_to_with_sentinel(&input_data, &mut encoded, Method::Auto, sentinel);
+        encoded.truncate(len);
+        if encoded.contains(&sentinel) {
+            return false;
+        }
+        encoded.push(sentinel);
+        DecodeMethod::iter().all(|method| {
+            let mut decoded = vec![0; decoded_size_upper_bound(encoded.len())];
+            try_cobs_decode_to_with_sentinel(&encoded, &mut decoded, method, sentinel)
+                .map(|len| decoded[..len] == input_data)
+                == Ok(true)
+        }) && try_cobs_decode_with_sentinel(&encoded, sentinel) == Ok(input_data)
+    }
+
+    #[test]
+    fn sentinel_encoding() {
+        let mut encoded = [0; 8];
+        let len =
+            cobs_encode_to_with_sentinel(&[0x11, 0x00, 0xFF], &mut encoded, Method::Trivial, 0xFF);
+        assert_eq!(encoded[..len], [0xFD, 0xEE, 0xFD, 0x00]);
+    }
+
+    #[test]
+    fn sentinel_in_frame() {
+        let mut output_data = [0; 8];
+        for method in DecodeMethod::iter() {
+            assert_eq!(
+                try_cobs_decode_to_with_sentinel(
+                    &[0x7D, 0x7E, 0x7C, 0x79],
+                    &mut output_data,
+                    method.clone(),
+                    0x7E
+                ),
+                Err(DecodeError::ZeroInInput { offset: 1 })
+            );
+            // Zero bytes are regular data with a sentinel
+            assert_eq!(
+                try_cobs_decode_to_with_sentinel(
+                    &[0x7D, 0x7F, 0x00, 0x7C, 0x79],
+                    &mut output_data,
+                    method,
+                    0x7E
+                ),
+                Ok(4)
+            );
+        }
+    }
+
+    #[cfg(feature = "alloc")]
     #[test]
     fn decoding_no_zeros_short() {
         assert_eq!(
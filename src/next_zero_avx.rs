@@ -0,0 +1,119 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86::{
+    __m256i, _mm256_cmpeq_epi8, _mm256_loadu_si256, _mm256_movemask_epi8, _mm256_set1_epi8,
+};
+#[cfg(target_arch = "x86")]
+use core::arch::x86::{__m512i, _mm512_cmpeq_epi8_mask, _mm512_loadu_si512, _mm512_set1_epi8};
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::{
+    __m256i, _mm256_cmpeq_epi8, _mm256_loadu_si256, _mm256_movemask_epi8, _mm256_set1_epi8,
+};
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::{__m512i, _mm512_cmpeq_epi8_mask, _mm512_loadu_si512, _mm512_set1_epi8};
+
+use crate::{
+    block_iter::{NextZeroIndex, PortableBlocks},
+    cpu_features::{has_avx2, has_avx512bw},
+};
+
+/// Zero search using AVX2 intrinsics, 32 bytes at a time
+///
+/// Falls back to the portable zero search on CPUs without AVX2.
+#[derive(Default)]
+pub struct SimdBlocksAvx2 {}
+
+impl NextZeroIndex for SimdBlocksAvx2 {
+    #[inline]
+    fn next_byte_index(data: &[u8], byte: u8) -> Option<usize> {
+        if has_avx2() {
+            // SAFETY: CPU support for AVX2 was checked
+            unsafe { next_byte_index_avx2(data, byte) }
+        } else {
+            PortableBlocks::next_byte_index(data, byte)
+        }
+    }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn next_byte_index_avx2(data: &[u8], byte: u8) -> Option<usize> {
+    let needle = _mm256_set1_epi8(byte as i8);
+    let mut nonzero_bytes = 0;
+    let mut chunks_iter = data.chunks_exact(32);
+
+    for block in chunks_iter.by_ref() {
+        let v = _mm256_loadu_si256(block.as_ptr() as *const __m256i);
+        // One bit per byte, lowest bit for first byte
+        let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(v, needle)) as u32;
+        if mask != 0 {
+            return Some(nonzero_bytes + mask.trailing_zeros() as usize);
+        }
+        nonzero_bytes += 32;
+    }
+
+    chunks_iter
+        .remainder()
+        .iter()
+        .position(|b| *b == byte)
+        .map(|i| nonzero_bytes + i)
+}
+
+/// Zero search using AVX-512BW intrinsics, 64 bytes at a time
+///
+/// Falls back to the AVX2 zero search on CPUs without AVX-512BW.
+#[derive(Default)]
+pub struct SimdBlocksAvx512 {}
+
+impl NextZeroIndex for SimdBlocksAvx512 {
+    #[inline]
+    fn next_byte_index(data: &[u8], byte: u8) -> Option<usize> {
+        if has_avx512bw() {
+            // SAFETY: CPU support for AVX-512BW was checked
+            unsafe { next_byte_index_avx512(data, byte) }
+        } else {
+            SimdBlocksAvx2::next_byte_index(data, byte)
+        }
+    }
+}
+
+#[target_feature(enable = "avx512bw")]
+unsafe fn next_byte_index_avx512(data: &[u8], byte: u8) -> Option<usize> {
+    let needle = _mm512_set1_epi8(byte as i8);
+    let mut nonzero_bytes = 0;
+    let mut chunks_iter = data.chunks_exact(64);
+
+    for block in chunks_iter.by_ref() {
+        let v = _mm512_loadu_si512(block.as_ptr() as *const __m512i);
+        // Comparison directly yields a bitmask, lowest bit for first byte
+        let mask = _mm512_cmpeq_epi8_mask(v, needle);
+        if mask != 0 {
+            return Some(nonzero_bytes + mask.trailing_zeros() as usize);
+        }
+        nonzero_bytes += 64;
+    }
+
+    chunks_iter
+        .remainder()
+        .iter()
+        .position(|b| *b == byte)
+        .map(|i| nonzero_bytes + i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SimdBlocksAvx2, SimdBlocksAvx512};
+    use crate::block_iter::NextZeroIndex;
+
+    #[test]
+    fn no_zero() {
+        assert_eq!(SimdBlocksAvx2::next_zero_index(&[1; 100]), None);
+        assert_eq!(SimdBlocksAvx512::next_zero_index(&[1; 200]), None);
+    }
+
+    #[test]
+    fn zero_in_last_lane() {
+        let mut data = [1; 128];
+        data[127] = 0;
+        assert_eq!(SimdBlocksAvx2::next_zero_index(&data), Some(127));
+        assert_eq!(SimdBlocksAvx512::next_zero_index(&data), Some(127));
+    }
+}
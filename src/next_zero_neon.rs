@@ -0,0 +1,54 @@
+use core::arch::aarch64::{
+    vceqq_u8, vdupq_n_u8, vget_lane_u64, vld1q_u8, vreinterpret_u64_u8, vreinterpretq_u16_u8,
+    vshrn_n_u16,
+};
+
+use crate::block_iter::NextZeroIndex;
+
+/// Zero search using NEON intrinsics, 16 bytes at a time
+#[derive(Default)]
+pub struct SimdBlocksNeon {}
+
+impl NextZeroIndex for SimdBlocksNeon {
+    fn next_byte_index(data: &[u8], byte: u8) -> Option<usize> {
+        let mut nonzero_bytes = 0;
+        let mut chunks_iter = data.chunks_exact(16);
+
+        for block in chunks_iter.by_ref() {
+            // SAFETY: NEON is part of the aarch64 baseline, block has exactly 16 bytes
+            let mask = unsafe {
+                let eq = vceqq_u8(vld1q_u8(block.as_ptr()), vdupq_n_u8(byte));
+                // NEON has no movemask, shifting right and narrowing each 16 bit lane by 4 packs
+                // the comparison result into 64 bits, 4 bits per byte, lowest bits for first byte
+                let narrowed = vshrn_n_u16::<4>(vreinterpretq_u16_u8(eq));
+                vget_lane_u64::<0>(vreinterpret_u64_u8(narrowed))
+            };
+            if mask != 0 {
+                return Some(nonzero_bytes + (mask.trailing_zeros() / 4) as usize);
+            }
+            nonzero_bytes += 16;
+        }
+
+        chunks_iter
+            .remainder()
+            .iter()
+            .position(|b| *b == byte)
+            .map(|i| nonzero_bytes + i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SimdBlocksNeon;
+    use crate::block_iter::NextZeroIndex;
+
+    #[test]
+    fn zero_in_each_lane() {
+        for i in 0..16 {
+            let mut data = [1; 16];
+            data[i] = 0;
+            assert_eq!(SimdBlocksNeon::next_zero_index(&data), Some(i));
+        }
+        assert_eq!(SimdBlocksNeon::next_zero_index(&[1; 16]), None);
+    }
+}
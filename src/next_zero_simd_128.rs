@@ -1,22 +1,55 @@
-use std::{
-    arch::x86_64::{__m128i, _mm_cmpestri, _mm_setzero_si128, _SIDD_CMP_EQUAL_ORDERED},
-    simd::u8x16,
-};
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub use sse42::SimdBlocks16;
 
-use crate::block_iter::NextZeroIndex;
+/// On targets without SSE4.2 intrinsics, the portable zero search is used instead
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub type SimdBlocks16 = crate::block_iter::PortableBlocks;
 
-#[derive(Default)]
-pub struct SimdBlocks16 {}
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod sse42 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::{
+        __m128i, _mm_cmpestri, _mm_loadu_si128, _mm_set1_epi8, _SIDD_CMP_EQUAL_ORDERED,
+    };
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::{
+        __m128i, _mm_cmpestri, _mm_loadu_si128, _mm_set1_epi8, _SIDD_CMP_EQUAL_ORDERED,
+    };
 
-impl NextZeroIndex for SimdBlocks16 {
-    fn next_zero_index(data: &[u8]) -> Option<usize> {
+    use crate::{
+        block_iter::{NextZeroIndex, PortableBlocks},
+        cpu_features::has_sse42,
+    };
+
+    /// Zero search using SSE4.2 string instructions, 16 bytes at a time
+    ///
+    /// Falls back to the portable zero search on CPUs without SSE4.2.
+    #[derive(Default)]
+    pub struct SimdBlocks16 {}
+
+    impl NextZeroIndex for SimdBlocks16 {
+        #[inline]
+        fn next_byte_index(data: &[u8], byte: u8) -> Option<usize> {
+            if has_sse42() {
+                // SAFETY: CPU support for SSE4.2 was checked
+                unsafe { next_byte_index_sse42(data, byte) }
+            } else {
+                PortableBlocks::next_byte_index(data, byte)
+            }
+        }
+    }
+
+    #[target_feature(enable = "sse4.2")]
+    unsafe fn next_byte_index_sse42(data: &[u8], byte: u8) -> Option<usize> {
+        // Needle of length 1
+        let needle = _mm_set1_epi8(byte as i8);
         let mut nonzero_bytes = 0;
 
         //for block in AlignedIter::new(data, 16) { // worse performance :(
         for block in data.chunks(16) {
             if block.len() != 16 {
                 for b in block {
-                    if *b == 0 {
+                    if *b == byte {
                         return Some(nonzero_bytes);
                     } else {
                         nonzero_bytes += 1;
@@ -25,12 +58,11 @@ impl NextZeroIndex for SimdBlocks16 {
                 continue;
             }
 
-            let v = u8x16::from_slice(block);
             let res = unsafe {
                 _mm_cmpestri(
-                    _mm_setzero_si128(),
+                    needle,
                     1,
-                    __m128i::from(v),
+                    _mm_loadu_si128(block.as_ptr() as *const __m128i),
                     16,
                     _SIDD_CMP_EQUAL_ORDERED,
                 )
@@ -44,3 +76,14 @@ impl NextZeroIndex for SimdBlocks16 {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SimdBlocks16;
+    use crate::block_iter::NextZeroIndex;
+
+    #[test]
+    fn no_zero() {
+        assert_eq!(SimdBlocks16::next_zero_index(&[1; 100]), None);
+    }
+}
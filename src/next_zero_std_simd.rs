@@ -1,63 +1,39 @@
 use crate::block_iter::NextZeroIndex;
 
-use num::PrimInt;
-use std::simd::prelude::*;
-use std::simd::LaneCount;
-use std::simd::SupportedLaneCount;
-use std::simd::ToBitMask;
+use core::simd::prelude::*;
 
+/// Zero search using std::simd with `N` lanes, lowered to whatever the compile-time target supports
 #[derive(Default)]
-pub struct SimdBlocksGeneric<const N: usize>
-where
-    LaneCount<N>: SupportedLaneCount,
-    Mask<i8, N>: ToBitMask,
-    <Mask<i8, N> as ToBitMask>::BitMask: PrimInt, {}
+pub struct SimdBlocksGeneric<const N: usize> {}
 
-impl<const N: usize> NextZeroIndex for SimdBlocksGeneric<N>
-where
-    LaneCount<N>: SupportedLaneCount,
-    Mask<i8, N>: ToBitMask,
-    <Mask<i8, N> as ToBitMask>::BitMask: PrimInt,
-{
-    fn next_zero_index(data: &[u8]) -> Option<usize> {
+impl<const N: usize> NextZeroIndex for SimdBlocksGeneric<N> {
+    fn next_byte_index(data: &[u8], byte: u8) -> Option<usize> {
         let mut nonzero_bytes = 0;
-        let mut chunks_iter = data.array_chunks::<N>();
+        let (chunks, remainder) = data.as_chunks::<N>();
 
-        for block in chunks_iter.by_ref() {
-            let index = first_zero_in_vector::<N>(Simd::from(*block));
-            nonzero_bytes += index;
-            if (index as usize) < N {
-                return Some(nonzero_bytes as usize);
+        for block in chunks {
+            if let Some(index) = first_byte_in_vector::<N>(Simd::from_array(*block), byte) {
+                return Some(nonzero_bytes + index);
             }
+            nonzero_bytes += N;
         }
 
-        for b in chunks_iter.remainder() {
-            if *b == 0 {
-                return Some(nonzero_bytes as usize);
-            } else {
-                nonzero_bytes += 1;
-            }
-        }
-        None
+        remainder
+            .iter()
+            .position(|b| *b == byte)
+            .map(|i| nonzero_bytes + i)
     }
 }
 
 // 256b vector: 32xu8
-pub fn first_zero_in_vector<const N: usize>(block: Simd<u8, N>) -> u32
-where
-    LaneCount<N>: SupportedLaneCount,
-    Mask<i8, N>: ToBitMask,
-    <Mask<i8, N> as ToBitMask>::BitMask: PrimInt,
-{
-    // Equality check creates mask, mask to int ?u256???, clz instruction
-    let mask = block.simd_eq(Simd::<u8, N>::splat(0u8));
-    let bitmask = mask.to_bitmask();
-    bitmask.trailing_zeros()
+pub fn first_byte_in_vector<const N: usize>(block: Simd<u8, N>, byte: u8) -> Option<usize> {
+    // Equality check creates mask, lowest set lane is the first match
+    block.simd_eq(Simd::<u8, N>::splat(byte)).first_set()
 }
 
 #[cfg(test)]
 mod tests {
-    use std::simd::{Simd, SimdPartialEq, ToBitMask};
+    use std::simd::prelude::*;
 
     #[test]
     fn bitmask_assumptions() {
@@ -69,7 +45,7 @@ mod tests {
         let result_mask = input_vec.simd_eq(search);
         println!("{:?}", result_mask);
         // Bitmask: index starting at lowest bit
-        let result_bitmask: u32 = result_mask.to_bitmask();
+        let result_bitmask: u64 = result_mask.to_bitmask();
         println!("{:#032b}", result_bitmask);
         assert!(result_bitmask & (1 << 7) != 0);
         assert!(result_bitmask & (1 << 0) != 0);
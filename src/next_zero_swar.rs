@@ -0,0 +1,68 @@
+use core::mem::size_of;
+
+use crate::block_iter::NextZeroIndex;
+
+const WORD_SIZE: usize = size_of::<usize>();
+/// 0x0101..01
+const LOW_BITS: usize = usize::MAX / 0xFF;
+/// 0x8080..80
+const HIGH_BITS: usize = LOW_BITS * 0x80;
+
+/// Zero search loading one `usize` word at a time ("SIMD within a register")
+///
+/// Works on every target, including microcontrollers and wasm32 without simd128.
+#[derive(Default)]
+pub struct SimdBlocksSwar {}
+
+impl NextZeroIndex for SimdBlocksSwar {
+    fn next_byte_index(data: &[u8], byte: u8) -> Option<usize> {
+        // XOR turns the searched byte into zero
+        let pattern = LOW_BITS * byte as usize;
+        let mut nonzero_bytes = 0;
+        let mut chunks_iter = data.chunks_exact(WORD_SIZE);
+
+        for block in chunks_iter.by_ref() {
+            // Little endian so that the lowest byte is the first byte on every target
+            let x = usize::from_le_bytes(block.try_into().unwrap()) ^ pattern;
+            // High bit set for every zero byte. Bytes above a zero byte may be wrongly flagged due to the borrow,
+            // but the lowest flagged byte is always the first zero.
+            let mask = x.wrapping_sub(LOW_BITS) & !x & HIGH_BITS;
+            if mask != 0 {
+                return Some(nonzero_bytes + (mask.trailing_zeros() / 8) as usize);
+            }
+            nonzero_bytes += WORD_SIZE;
+        }
+
+        chunks_iter
+            .remainder()
+            .iter()
+            .position(|b| *b == byte)
+            .map(|i| nonzero_bytes + i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SimdBlocksSwar, WORD_SIZE};
+    use crate::block_iter::NextZeroIndex;
+
+    #[test]
+    fn zero_in_each_byte() {
+        for i in 0..WORD_SIZE {
+            let mut data = [1; WORD_SIZE];
+            data[i] = 0;
+            assert_eq!(SimdBlocksSwar::next_zero_index(&data), Some(i));
+        }
+    }
+
+    #[test]
+    fn borrow_does_not_cause_false_positives() {
+        // 0x01 directly after a zero borrows, 0x80 and 0xFF have the high bit set already
+        let mut data = [0x80; 2 * WORD_SIZE];
+        data[WORD_SIZE + 1] = 0;
+        data[WORD_SIZE + 2] = 0x01;
+        data[WORD_SIZE + 3] = 0xFF;
+        assert_eq!(SimdBlocksSwar::next_zero_index(&data), Some(WORD_SIZE + 1));
+        assert_eq!(SimdBlocksSwar::next_zero_index(&[0xFF; 100]), None);
+    }
+}
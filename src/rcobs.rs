@@ -0,0 +1,270 @@
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    block_iter::{GroupSplitter, Split},
+    strip_delimiter, DecodeError, DefaultBlocks,
+};
+
+/// Calculates the maximum rCOBS-encoded message size
+///
+/// In contrast to COBS, a group of 254 non-zero bytes at the end is followed by an empty group,
+/// since the streaming encoder cannot know that no more data follows.
+pub fn rcobs_encoded_size_upper_bound(input_size: usize) -> usize {
+    input_size + input_size / 254 + 1
+}
+
+/// Incremental rCOBS encoder
+///
+/// rCOBS (reverse COBS) places each overhead byte after its group instead of before it,
+/// so data is written out as soon as it is pushed, without buffering the open group.
+/// The encoder only keeps the length of the open group, and needs no allocation.
+/// The output does not include the frame delimiter.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::RcobsEncoder;
+///
+/// let mut encoder = RcobsEncoder::new();
+/// let mut encoded_output = [0; 8];
+/// let mut len = encoder.push(&[1, 3, 0], &mut encoded_output);
+/// assert_eq!(encoded_output[..len], [1, 3, 3]);
+/// len += encoder.push(&[7, 0, 8], &mut encoded_output[len..]);
+/// len += encoder.finish(&mut encoded_output[len..]);
+/// assert_eq!(encoded_output[..len], [1, 3, 3, 7, 2, 8, 2]);
+/// ```
+#[derive(Default)]
+pub struct RcobsEncoder {
+    splitter: GroupSplitter<DefaultBlocks>,
+}
+
+impl RcobsEncoder {
+    pub fn new() -> RcobsEncoder {
+        RcobsEncoder {
+            splitter: GroupSplitter::new(),
+        }
+    }
+
+    /// Encodes the next part of the frame, returning the number of bytes written to `output`.
+    ///
+    /// User must ensure that the buffer is big enough, the output always fits within
+    /// [rcobs_encoded_size_upper_bound]\(data.len()).
+    pub fn push(&mut self, mut data: &[u8], output: &mut [u8]) -> usize {
+        let mut out_idx = 0;
+        while let Some(split) = self.splitter.split(data) {
+            match split {
+                Split::Zero { len, group_len } => {
+                    // Zero terminates the open group
+                    output[out_idx..out_idx + len].copy_from_slice(&data[..len]);
+                    output[out_idx + len] = (group_len + 1) as u8;
+                    out_idx += len + 1;
+                    data = &data[len + 1..];
+                }
+                Split::Full { len } => {
+                    output[out_idx..out_idx + len].copy_from_slice(&data[..len]);
+                    output[out_idx + len] = 0xFF;
+                    out_idx += len + 1;
+                    data = &data[len..];
+                }
+                Split::Open { len } => {
+                    output[out_idx..out_idx + len].copy_from_slice(&data[..len]);
+                    out_idx += len;
+                    data = &data[len..];
+                }
+            }
+        }
+
+        out_idx
+    }
+
+    /// Completes the frame by writing the overhead byte of the final group, returning the number of bytes written (1).
+    ///
+    /// The encoder can be reused for the next frame afterwards.
+    pub fn finish(&mut self, output: &mut [u8]) -> usize {
+        output[0] = (self.splitter.run() + 1) as u8;
+        self.splitter.reset();
+        1
+    }
+}
+
+/// rCOBS-encode data to a buffer.
+///
+/// User must ensure that the buffer is big enough, the output always fits within [rcobs_encoded_size_upper_bound].
+/// See [RcobsEncoder] for encoding data piecewise.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{rcobs_encode_to, rcobs_encoded_size_upper_bound};
+///
+/// let input_data = [1, 3, 0, 7, 0, 8];
+/// let mut encoded_output = vec![0; rcobs_encoded_size_upper_bound(input_data.len())];
+/// let output_length = rcobs_encode_to(&input_data, &mut encoded_output);
+/// assert_eq!(encoded_output[..output_length], [1, 3, 3, 7, 2, 8, 2]);
+/// ```
+pub fn rcobs_encode_to(input: &[u8], output: &mut [u8]) -> usize {
+    let mut encoder = RcobsEncoder::new();
+    let len = encoder.push(input, output);
+    len + encoder.finish(&mut output[len..])
+}
+
+/// rCOBS-decode a frame to a buffer, rejecting malformed input.
+///
+/// The input may end with a single frame delimiter, any other zero byte is reported as error.
+/// Groups are decoded starting at the end of the frame, the decoded data is moved to the start of the buffer afterwards.
+/// Decoded data always fits within [decoded_size_upper_bound](crate::decoded_size_upper_bound)(input.len()).
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::try_rcobs_decode_to;
+///
+/// let mut decoded_output = [0; 8];
+/// assert_eq!(try_rcobs_decode_to(&[1, 3, 3, 7, 2, 8, 2, 0], &mut decoded_output), Ok(6));
+/// assert_eq!(decoded_output[..6], [1, 3, 0, 7, 0, 8]);
+/// ```
+pub fn try_rcobs_decode_to(input: &[u8], output: &mut [u8]) -> Result<usize, DecodeError> {
+    let input = strip_delimiter::<DefaultBlocks>(input, 0)?;
+
+    let mut in_idx = input.len();
+    let mut out_idx = output.len();
+    let mut last_group = true;
+    while in_idx > 0 {
+        let code_idx = in_idx - 1;
+        let code = input[code_idx] as usize;
+        if code - 1 > code_idx {
+            return Err(DecodeError::TruncatedGroup {
+                offset: code_idx,
+                expected: code - 1,
+                available: code_idx,
+            });
+        }
+
+        if code != 255 && !last_group {
+            // Group was terminated by a zero in the original data
+            out_idx = out_idx.checked_sub(1).ok_or(DecodeError::OutputTooSmall)?;
+            output[out_idx] = 0;
+        }
+        last_group = false;
+
+        in_idx = code_idx - (code - 1);
+        let block = &input[in_idx..code_idx];
+        out_idx = out_idx
+            .checked_sub(block.len())
+            .ok_or(DecodeError::OutputTooSmall)?;
+        // Copy all
+        output[out_idx..out_idx + block.len()].copy_from_slice(block);
+    }
+
+    let len = output.len() - out_idx;
+    output.copy_within(out_idx.., 0);
+    Ok(len)
+}
+
+/// rCOBS-decode a frame to a newly allocated vector, rejecting malformed input.
+///
+/// See [try_rcobs_decode_to] for details.
+#[cfg(feature = "alloc")]
+pub fn try_rcobs_decode(input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    let mut res = vec![0; input.len().saturating_sub(1)];
+    let len = try_rcobs_decode_to(input, &mut res)?;
+    res.truncate(len);
+    Ok(res)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::{rcobs_encode_to, rcobs_encoded_size_upper_bound, try_rcobs_decode, RcobsEncoder};
+    use crate::{cobs_encode_to_vec, DecodeError};
+
+    fn rcobs_encode(input: &[u8]) -> Vec<u8> {
+        let mut output = vec![0; rcobs_encoded_size_upper_bound(input.len())];
+        let len = rcobs_encode_to(input, &mut output);
+        output.truncate(len);
+        output
+    }
+
+    #[test]
+    fn vectors() {
+        let vectors: [(&[u8], &[u8]); 6] = [
+            (&[], &[0x01]),
+            (&[0x00], &[0x01, 0x01]),
+            (&[0x00, 0x00], &[0x01, 0x01, 0x01]),
+            (&[0x11, 0x22, 0x00, 0x33], &[0x11, 0x22, 0x03, 0x33, 0x02]),
+            (&[0x11, 0x22, 0x33, 0x44], &[0x11, 0x22, 0x33, 0x44, 0x05]),
+            (&[0x11, 0x00, 0x00, 0x00], &[0x11, 0x02, 0x01, 0x01, 0x01]),
+        ];
+        for (raw, encoded) in vectors {
+            assert_eq!(rcobs_encode(raw), encoded);
+            assert_eq!(try_rcobs_decode(encoded), Ok(raw.to_vec()));
+        }
+    }
+
+    #[test]
+    fn full_group() {
+        let mut raw = vec![0x11; 254];
+        let mut encoded = raw.clone();
+        encoded.extend_from_slice(&[0xFF, 0x01]);
+        assert_eq!(rcobs_encode(&raw), encoded);
+        assert_eq!(try_rcobs_decode(&encoded), Ok(raw.clone()));
+
+        raw.extend_from_slice(&[0x00, 0x22]);
+        encoded.truncate(255);
+        encoded.extend_from_slice(&[0x01, 0x22, 0x02]);
+        assert_eq!(rcobs_encode(&raw), encoded);
+        assert_eq!(try_rcobs_decode(&encoded), Ok(raw));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            try_rcobs_decode(&[0x11, 0x00, 0x02]),
+            Err(DecodeError::ZeroInInput { offset: 1 })
+        );
+        assert_eq!(
+            try_rcobs_decode(&[0x11, 0x03]),
+            Err(DecodeError::TruncatedGroup {
+                offset: 1,
+                expected: 2,
+                available: 1
+            })
+        );
+    }
+
+    #[quickcheck]
+    fn roundtrip(input_data: Vec<u8>) -> bool {
+        let mut encoded = rcobs_encode(&input_data);
+        encoded.push(0);
+        try_rcobs_decode(&encoded) == Ok(input_data)
+    }
+
+    #[quickcheck]
+    fn reversed_cobs(input_data: Vec<u8>) -> bool {
+        // Without complete groups, rCOBS is COBS backwards
+        let input_data: Vec<_> = input_data.into_iter().take(253).collect();
+        let mut reversed = input_data.clone();
+        reversed.reverse();
+        let mut encoded = rcobs_encode(&input_data);
+        encoded.reverse();
+        encoded == cobs_encode_to_vec(&reversed)
+    }
+
+    #[quickcheck]
+    fn chunked_input(input_data: Vec<u8>, chunk_size: u8) -> bool {
+        let mut encoder = RcobsEncoder::new();
+        let mut encoded = vec![0; rcobs_encoded_size_upper_bound(input_data.len())];
+        let mut len = 0;
+        for chunk in input_data.chunks(chunk_size as usize + 1) {
+            len += encoder.push(chunk, &mut encoded[len..]);
+        }
+        len += encoder.finish(&mut encoded[len..]);
+        encoded[..len] == rcobs_encode(&input_data)
+    }
+
+    #[quickcheck]
+    fn arbitrary_input_does_not_panic(input_data: Vec<u8>) -> bool {
+        let _ = try_rcobs_decode(&input_data);
+        true
+    }
+}
@@ -0,0 +1,203 @@
+use std::io::{self, BufRead};
+
+use crate::{
+    decoded_size_upper_bound,
+    frame_splitter::{FrameSplit, FrameSplitter},
+    try_cobs_decode_to, DecodeMethod,
+};
+
+/// [BufRead] adapter that yields the decoded COBS frames read from it
+///
+/// The buffer of the underlying reader is searched for delimiters with SIMD, encoded data of a frame
+/// is collected in an internal buffer which is reused for every frame.
+/// Consecutive delimiters do not end an empty frame, [CobsReader::read_frame] continues with the following data.
+///
+/// # Example
+///
+/// ```
+/// use cobs_simd::{CobsReader, DecodeMethod};
+///
+/// let mut reader = CobsReader::new(&[3, 1, 3, 2, 7, 0, 2, 8, 0][..], DecodeMethod::Simd16);
+/// let mut frame = Vec::new();
+/// assert_eq!(reader.read_frame(&mut frame)?, Some(4));
+/// assert_eq!(frame, [1, 3, 0, 7]);
+/// frame.clear();
+/// assert_eq!(reader.read_frame(&mut frame)?, Some(1));
+/// assert_eq!(frame, [8]);
+/// assert_eq!(reader.read_frame(&mut frame)?, None);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct CobsReader<R: BufRead> {
+    inner: R,
+    method: DecodeMethod,
+    encoded: Vec<u8>, // Encoded data of the current frame, without delimiter
+    splitter: FrameSplitter,
+}
+
+impl<R: BufRead> CobsReader<R> {
+    pub fn new(inner: R, method: DecodeMethod) -> CobsReader<R> {
+        CobsReader {
+            inner,
+            method,
+            encoded: Vec::new(),
+            splitter: FrameSplitter::new(usize::MAX),
+        }
+    }
+
+    /// Reads the next frame and appends the decoded data to `frame`.
+    ///
+    /// Returns the number of decoded bytes, or `None` if the reader is exhausted.
+    /// A malformed frame is reported as [io::ErrorKind::InvalidData], leaving `frame` unchanged.
+    /// Reading resumes with the next frame afterwards.
+    /// Data after the last delimiter is reported as [io::ErrorKind::UnexpectedEof].
+    pub fn read_frame(&mut self, frame: &mut Vec<u8>) -> io::Result<Option<usize>> {
+        loop {
+            let buf = match self.inner.fill_buf() {
+                Ok(buf) => buf,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            if buf.is_empty() {
+                if !self.splitter.finish() {
+                    return Ok(None);
+                }
+                self.encoded.clear();
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "stream ended within a frame",
+                ));
+            }
+
+            match self.splitter.split(buf) {
+                FrameSplit::Incomplete => {
+                    let len = buf.len();
+                    self.encoded.extend_from_slice(buf);
+                    self.inner.consume(len);
+                }
+                FrameSplit::Frame { end } => {
+                    self.encoded.extend_from_slice(&buf[..end]);
+                    self.inner.consume(end + 1);
+                    return self.decode_frame(frame).map(Some);
+                }
+                FrameSplit::Skipped(n) => self.inner.consume(n),
+                // Frames have no maximum length
+                FrameSplit::TooLong(_) => unreachable!(),
+            }
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader, discarding data of a partially read frame.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn decode_frame(&mut self, frame: &mut Vec<u8>) -> io::Result<usize> {
+        let start = frame.len();
+        frame.resize(start + decoded_size_upper_bound(self.encoded.len()), 0);
+        let res = try_cobs_decode_to(&self.encoded, &mut frame[start..], self.method.clone());
+        self.encoded.clear();
+        match res {
+            Ok(len) => {
+                frame.truncate(start + len);
+                Ok(len)
+            }
+            Err(e) => {
+                frame.truncate(start);
+                Err(io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, ErrorKind, Write};
+
+    use strum::IntoEnumIterator;
+
+    use super::CobsReader;
+    use crate::{CobsWriter, DecodeError, DecodeMethod, Method};
+
+    fn read_frames<R: std::io::BufRead>(reader: &mut CobsReader<R>) -> Vec<Vec<u8>> {
+        let mut frames = vec![];
+        let mut frame = vec![];
+        while reader.read_frame(&mut frame).unwrap().is_some() {
+            frames.push(std::mem::take(&mut frame));
+        }
+        frames
+    }
+
+    #[quickcheck]
+    fn writer_roundtrip(frames: Vec<Vec<u8>>, capacity: u8) -> bool {
+        let frames: Vec<_> = frames.into_iter().filter(|f| !f.is_empty()).collect();
+        let mut writer = CobsWriter::frame_per_write(Vec::new(), Method::Auto);
+        for frame in &frames {
+            writer.write_all(frame).unwrap();
+        }
+        let stream = writer.into_inner();
+
+        DecodeMethod::iter().all(|method| {
+            // Small buffers split frames across reads
+            let inner = BufReader::with_capacity(capacity as usize + 1, &stream[..]);
+            read_frames(&mut CobsReader::new(inner, method)) == frames
+        })
+    }
+
+    #[test]
+    fn appends_to_frame() {
+        let mut reader = CobsReader::new(&[0x02, 0x11, 0x00][..], DecodeMethod::Simd16);
+        let mut frame = vec![0x22];
+        assert_eq!(reader.read_frame(&mut frame).unwrap(), Some(1));
+        assert_eq!(frame, [0x22, 0x11]);
+    }
+
+    #[test]
+    fn skips_empty_frames() {
+        let mut reader = CobsReader::new(&[0x00, 0x00, 0x01, 0x00, 0x00][..], DecodeMethod::Simd16);
+        assert_eq!(read_frames(&mut reader), [vec![]]);
+    }
+
+    #[test]
+    fn resynchronises_after_invalid_frame() {
+        let stream = [0x05, 0x11, 0x00, 0x02, 0x22, 0x00];
+        let mut reader = CobsReader::new(&stream[..], DecodeMethod::Simd16);
+        let mut frame = vec![];
+
+        let error = reader.read_frame(&mut frame).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            error.into_inner().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::TruncatedGroup {
+                offset: 0,
+                expected: 4,
+                available: 1
+            })
+        );
+        assert!(frame.is_empty());
+
+        assert_eq!(reader.read_frame(&mut frame).unwrap(), Some(1));
+        assert_eq!(frame, [0x22]);
+        assert_eq!(reader.read_frame(&mut frame).unwrap(), None);
+    }
+
+    #[test]
+    fn unterminated_frame() {
+        let mut reader = CobsReader::new(&[0x02, 0x11, 0x00, 0x02, 0x22][..], DecodeMethod::Simd16);
+        let mut frame = vec![];
+        assert_eq!(reader.read_frame(&mut frame).unwrap(), Some(1));
+        assert_eq!(
+            reader.read_frame(&mut frame).unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+        assert_eq!(reader.read_frame(&mut frame).unwrap(), None);
+    }
+}
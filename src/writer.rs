@@ -0,0 +1,172 @@
+use std::io::{self, Write};
+
+use crate::{cobs_encode_to, encoded_size_upper_bound, Method};
+
+/// [Write] adapter that COBS-encodes frames written to it
+///
+/// Created with [CobsWriter::new], data is collected until [CobsWriter::end_frame] is called,
+/// which encodes it as one frame followed by the zero delimiter.
+/// Created with [CobsWriter::frame_per_write], every call to `write` (or `write_all`) is encoded as a frame on its own.
+/// Writing empty data writes nothing, so empty frames are written with [CobsWriter::end_frame] in both modes.
+///
+/// Buffers for frame data and encoded output are reused, so there is no allocation per frame once they have grown.
+/// Data of an unfinished frame is discarded when the writer is dropped.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write;
+/// use cobs_simd::{CobsWriter, Method};
+///
+/// let mut writer = CobsWriter::new(Vec::new(), Method::Auto);
+/// writer.write_all(&[1, 3, 0])?;
+/// writer.write_all(&[7, 0, 8])?;
+/// writer.end_frame()?;
+/// assert_eq!(writer.into_inner(), [3, 1, 3, 2, 7, 2, 8, 0]);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct CobsWriter<W: Write> {
+    inner: W,
+    method: Method,
+    frame_per_write: bool,
+    frame: Vec<u8>,   // Data of the current frame
+    encoded: Vec<u8>, // Encoded frame and delimiter
+}
+
+impl<W: Write> CobsWriter<W> {
+    /// Creates a writer which encodes all data written until [CobsWriter::end_frame] as one frame.
+    pub fn new(inner: W, method: Method) -> CobsWriter<W> {
+        CobsWriter {
+            inner,
+            method,
+            frame_per_write: false,
+            frame: Vec::new(),
+            encoded: Vec::new(),
+        }
+    }
+
+    /// Creates a writer which encodes the data of every `write` call as a separate frame.
+    pub fn frame_per_write(inner: W, method: Method) -> CobsWriter<W> {
+        CobsWriter {
+            frame_per_write: true,
+            ..CobsWriter::new(inner, method)
+        }
+    }
+
+    /// Encodes the data written since the previous frame, and writes it followed by the delimiter.
+    ///
+    /// Without data, an empty frame is written.
+    pub fn end_frame(&mut self) -> io::Result<()> {
+        let frame = core::mem::take(&mut self.frame);
+        let res = self.write_frame(&frame);
+        self.frame = frame;
+        self.frame.clear();
+        res
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the underlying writer, discarding data of an unfinished frame.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_frame(&mut self, data: &[u8]) -> io::Result<()> {
+        self.encoded
+            .resize(encoded_size_upper_bound(data.len()) + 1, 0);
+        let len = cobs_encode_to(data, &mut self.encoded, self.method.clone());
+        self.encoded[len] = 0;
+        self.inner.write_all(&self.encoded[..len + 1])
+    }
+}
+
+impl<W: Write> Write for CobsWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.frame_per_write {
+            self.write_frame(buf)?;
+        } else {
+            self.frame.extend_from_slice(buf);
+        }
+        Ok(buf.len())
+    }
+
+    /// Flushes the underlying writer, without ending the current frame.
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use strum::IntoEnumIterator;
+
+    use super::CobsWriter;
+    use crate::{cobs_encode_to_vec, Method};
+
+    fn framed(data: &[u8]) -> Vec<u8> {
+        let mut res = cobs_encode_to_vec(data);
+        res.push(0);
+        res
+    }
+
+    #[quickcheck]
+    fn frames(frames: Vec<Vec<u8>>, chunk_size: u8) -> bool {
+        Method::iter().all(|method| {
+            let mut writer = CobsWriter::new(Vec::new(), method);
+            for frame in &frames {
+                for chunk in frame.chunks(chunk_size as usize + 1) {
+                    writer.write_all(chunk).unwrap();
+                }
+                writer.end_frame().unwrap();
+            }
+            writer.into_inner() == frames.iter().flat_map(|f| framed(f)).collect::<Vec<_>>()
+        })
+    }
+
+    #[quickcheck]
+    fn frame_per_write(frames: Vec<Vec<u8>>) -> bool {
+        let mut writer = CobsWriter::frame_per_write(Vec::new(), Method::Auto);
+        for frame in &frames {
+            if frame.is_empty() {
+                writer.end_frame().unwrap();
+            } else {
+                writer.write_all(frame).unwrap();
+            }
+        }
+        writer.into_inner() == frames.iter().flat_map(|f| framed(f)).collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn empty_frame() {
+        let mut writer = CobsWriter::new(Vec::new(), Method::Auto);
+        writer.end_frame().unwrap();
+        assert_eq!(writer.get_ref(), &[0x01, 0x00]);
+    }
+
+    #[test]
+    fn empty_write_writes_nothing() {
+        let mut writer = CobsWriter::frame_per_write(Vec::new(), Method::Auto);
+        assert_eq!(writer.write(&[]).unwrap(), 0);
+        assert!(writer.get_ref().is_empty());
+    }
+
+    #[test]
+    fn unfinished_frame_is_not_written() {
+        let mut writer = CobsWriter::new(Vec::new(), Method::Auto);
+        writer.write_all(&[0x11, 0x22]).unwrap();
+        writer.flush().unwrap();
+        assert!(writer.into_inner().is_empty());
+    }
+}